@@ -16,6 +16,9 @@
 //   pursue reduced code duplication
 //
 
+use std::cmp::{min, max};
+use std::ptr::NonNull;
+use std::rc::Rc;
 
 macro_rules! IFC {
     ($e: expr) => {
@@ -30,9 +33,85 @@ macro_rules! IFC {
 
 type LONG = i32;
 type INT = i32;
+type UINT = u32;
 type LONGLONG = i64;
 type BYTE = u8;
 
+// Subpixel grid resolution this rasterizer hard-codes throughout (see
+// ConvertSubpixelXToPixel/ConvertSubpixelYToPixel): 3 bits of subpixel
+// precision, i.e. 8 subpixel rows per pixel row (the 8x8 overscale).
+const c_nShift: u32 = 3;
+const c_rInvShiftSize: f32 = 1.0 / 8.0;
+
+//-------------------------------------------------------------------------
+//
+//  Function:   HRESULT plumbing
+//
+//  Synopsis:
+//      The minimal slice of the Win32 HRESULT convention this file's
+//      untranslated-C++ sections assume throughout (S_OK/FAILED/RRETURN).
+//      Real failure codes beyond S_OK/E_NOTIMPL (e.g. WGXERR_VALUEOVERFLOW)
+//      come from headers this crate doesn't have, so callers that check
+//      for a specific code still can't build without those -- this just
+//      gives the ubiquitous success/failure plumbing a real type.
+//
+//-------------------------------------------------------------------------
+type HRESULT = i32;
+const S_OK: HRESULT = 0;
+const E_NOTIMPL: HRESULT = 0x80004001u32 as HRESULT;
+
+fn FAILED(hr: HRESULT) -> bool
+{
+    hr < 0
+}
+
+fn RRETURN(hr: HRESULT) -> HRESULT
+{
+    hr
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   Int32x32To64
+//
+//  Synopsis:
+//      Sign-extending 32x32->64 multiply -- the MSVC intrinsic of the
+//      same name the original C++ uses to get a 64-bit product without
+//      promoting either operand to 64 bits first.  Plain `as i64`
+//      widening before multiplying has the same effect in Rust.
+//
+//-------------------------------------------------------------------------
+fn Int32x32To64(a: INT, b: INT) -> LONGLONG
+{
+    (a as LONGLONG) * (b as LONGLONG)
+}
+
+//-------------------------------------------------------------------------
+//
+//  Struct:     CEdge
+//
+//  Synopsis:
+//      One edge of the active/inactive edge list the scanline sweep
+//      walks.  This is the minimal real field set the DDA-advance
+//      kernels (AdvanceDDAMultipleSteps, AdvanceDDAOneEdgeScalar,
+//      AdvanceDDAMultipleStepsSimd8, WindingBoundaryRight) actually read;
+//      other baseline pseudocode in this file that references CEdge
+//      pointers via the untranslated C++ sections is still assumed
+//      rather than checked against this definition.
+//
+//-------------------------------------------------------------------------
+struct CEdge {
+    X: INT,
+    StartY: INT,
+    EndY: INT,
+    WindingDirection: INT,
+    Dx: INT,
+    ErrorUp: INT,
+    ErrorDown: INT,
+    Error: INT,
+    Next: NonNull<CEdge>,
+}
+
 //-------------------------------------------------------------------------
 //
 // Coordinate system encoding
@@ -142,8 +221,11 @@ IsFractionLessThan(
 //  Synopsis:
 //     Advance the DDA by multiple steps
 //
+//  Unsafe because it dereferences the raw NonNull<CEdge> pointers the
+//  active-edge list is built from, same as AdvanceDDAOneEdgeScalar.
+//
 //-------------------------------------------------------------------------
-fn
+unsafe fn
 AdvanceDDAMultipleSteps(
     pEdgeLeft: NonNull<CEdge>,         // Left edge from active edge list
     pEdgeRight: NonNull<CEdge>,        // Right edge from active edge list
@@ -168,10 +250,10 @@ AdvanceDDAMultipleSteps(
     nDbgPixelCoordinateMax = (1 << 26);
     nDbgPixelCoordinateMin = -nDbgPixelCoordinateMax;
 
-    assert!(pEdgeLeft.X >= nDbgPixelCoordinateMin && pEdgeLeft.X <= nDbgPixelCoordinateMax);
-    assert!(pEdgeLeft.EndY >= nDbgPixelCoordinateMin && pEdgeLeft.EndY <= nDbgPixelCoordinateMax);
-    assert!(pEdgeRight.X >= nDbgPixelCoordinateMin && pEdgeRight.X <= nDbgPixelCoordinateMax);
-    assert!(pEdgeRight.EndY >= nDbgPixelCoordinateMin && pEdgeRight.EndY <= nDbgPixelCoordinateMax);
+    assert!(pEdgeLeft.as_ref().X >= nDbgPixelCoordinateMin && pEdgeLeft.as_ref().X <= nDbgPixelCoordinateMax);
+    assert!(pEdgeLeft.as_ref().EndY >= nDbgPixelCoordinateMin && pEdgeLeft.as_ref().EndY <= nDbgPixelCoordinateMax);
+    assert!(pEdgeRight.as_ref().X >= nDbgPixelCoordinateMin && pEdgeRight.as_ref().X <= nDbgPixelCoordinateMax);
+    assert!(pEdgeRight.as_ref().EndY >= nDbgPixelCoordinateMin && pEdgeRight.as_ref().EndY <= nDbgPixelCoordinateMax);
 
     //
     //        errorDown: (0, 2^30)
@@ -182,14 +264,14 @@ AdvanceDDAMultipleSteps(
     //
 
     let nDbgErrorDownMax: INT = (1 << 30);
-    assert!(pEdgeLeft.ErrorDown  > 0 && pEdgeLeft.ErrorDown  < nDbgErrorDownMax);
-    assert!(pEdgeRight.ErrorDown > 0 && pEdgeRight.ErrorDown < nDbgErrorDownMax);
+    assert!(pEdgeLeft.as_ref().ErrorDown  > 0 && pEdgeLeft.as_ref().ErrorDown  < nDbgErrorDownMax);
+    assert!(pEdgeRight.as_ref().ErrorDown > 0 && pEdgeRight.as_ref().ErrorDown < nDbgErrorDownMax);
 
     //
     //          errorUp: [0, errorDown)
     //
-    assert!(pEdgeLeft.ErrorUp  >= 0 && pEdgeLeft.ErrorUp  < pEdgeLeft.ErrorDown);
-    assert!(pEdgeRight.ErrorUp >= 0 && pEdgeRight.ErrorUp < pEdgeRight.ErrorDown);
+    assert!(pEdgeLeft.as_ref().ErrorUp  >= 0 && pEdgeLeft.as_ref().ErrorUp  < pEdgeLeft.as_ref().ErrorDown);
+    assert!(pEdgeRight.as_ref().ErrorUp >= 0 && pEdgeRight.as_ref().ErrorUp < pEdgeRight.as_ref().ErrorDown);
     }
 
     //
@@ -197,14 +279,14 @@ AdvanceDDAMultipleSteps(
     //
 
     // Since each point on the edge is withing 28.4 space, the following computation can't overflow.
-    nSubpixelXLeftBottom = pEdgeLeft.X + nSubpixelYAdvance*pEdgeLeft.Dx;
+    nSubpixelXLeftBottom = pEdgeLeft.as_ref().X + nSubpixelYAdvance*pEdgeLeft.as_ref().Dx;
 
     // Since the error values can be close to 2^30, we can get an overflow by multiplying with yAdvance.
     // So, we need to use a 64-bit temporary in this case.
-    let llSubpixelErrorBottom = pEdgeLeft.Error + Int32x32To64(nSubpixelYAdvance, pEdgeLeft.ErrorUp);
+    let llSubpixelErrorBottom = pEdgeLeft.as_ref().Error + Int32x32To64(nSubpixelYAdvance, pEdgeLeft.as_ref().ErrorUp);
     if (llSubpixelErrorBottom >= 0)
     {
-        let llSubpixelXLeftDelta = llSubpixelErrorBottom / (pEdgeLeft.ErrorDown as LONGLONG);
+        let llSubpixelXLeftDelta = llSubpixelErrorBottom / (pEdgeLeft.as_ref().ErrorDown as LONGLONG);
 
         // The delta should remain in range since it still represents a delta along the edge which
         // we know fits entirely in 28.4.  Note that we add one here since the error must end up
@@ -213,13 +295,13 @@ AdvanceDDAMultipleSteps(
         let nSubpixelXLeftDelta: INT = (llSubpixelXLeftDelta as INT) + 1;
 
         nSubpixelXLeftBottom += nSubpixelXLeftDelta;
-        llSubpixelErrorBottom -= Int32x32To64(pEdgeLeft.ErrorDown, nSubpixelXLeftDelta);
+        llSubpixelErrorBottom -= Int32x32To64(pEdgeLeft.as_ref().ErrorDown, nSubpixelXLeftDelta);
     }
 
     // At this point, the subtraction above should have generated an error that is within
     // (-pLeft->ErrorDown, 0)
 
-    assert!((llSubpixelErrorBottom > -pEdgeLeft.ErrorDown) && (llSubpixelErrorBottom < 0));
+    assert!((llSubpixelErrorBottom > -pEdgeLeft.as_ref().ErrorDown) && (llSubpixelErrorBottom < 0));
     *nSubpixelErrorLeftBottom = (llSubpixelErrorBottom as INT);
 
     //
@@ -227,14 +309,14 @@ AdvanceDDAMultipleSteps(
     //
 
     // Since each point on the edge is withing 28.4 space, the following computation can't overflow.
-    nSubpixelXRightBottom = pEdgeRight.X + nSubpixelYAdvance*pEdgeRight.Dx;
+    nSubpixelXRightBottom = pEdgeRight.as_ref().X + nSubpixelYAdvance*pEdgeRight.as_ref().Dx;
 
     // Since the error values can be close to 2^30, we can get an overflow by multiplying with yAdvance.
     // So, we need to use a 64-bit temporary in this case.
-    llSubpixelErrorBottom = pEdgeRight.Error + Int32x32To64(nSubpixelYAdvance, pEdgeRight.ErrorUp);
+    llSubpixelErrorBottom = pEdgeRight.as_ref().Error + Int32x32To64(nSubpixelYAdvance, pEdgeRight.as_ref().ErrorUp);
     if (llSubpixelErrorBottom >= 0)
     {
-        let llSubpixelXRightDelta: LONGLONG = llSubpixelErrorBottom / (pEdgeRight.ErrorDown as LONGLONG);
+        let llSubpixelXRightDelta: LONGLONG = llSubpixelErrorBottom / (pEdgeRight.as_ref().ErrorDown as LONGLONG);
 
         // The delta should remain in range since it still represents a delta along the edge which
         // we know fits entirely in 28.4.  Note that we add one here since the error must end up
@@ -243,13 +325,13 @@ AdvanceDDAMultipleSteps(
         let nSubpixelXRightDelta: INT = (llSubpixelXRightDelta as INT) + 1;
 
         nSubpixelXRightBottom += nSubpixelXRightDelta;
-        llSubpixelErrorBottom -= Int32x32To64(pEdgeRight.ErrorDown, nSubpixelXRightDelta);
+        llSubpixelErrorBottom -= Int32x32To64(pEdgeRight.as_ref().ErrorDown, nSubpixelXRightDelta);
     }
 
     // At this point, the subtraction above should have generated an error that is within
     // (-pRight->ErrorDown, 0)
 
-    assert!((llSubpixelErrorBottom > -pEdgeRight.ErrorDown) && (llSubpixelErrorBottom < 0));
+    assert!((llSubpixelErrorBottom > -pEdgeRight.as_ref().ErrorDown) && (llSubpixelErrorBottom < 0));
     *nSubpixelErrorRightBottom = (llSubpixelErrorBottom as INT);
 }
 
@@ -261,8 +343,11 @@ AdvanceDDAMultipleSteps(
 //     Compute some value that is >= nSubpixelAdvanceY*|1/m| where m is the
 //     slope defined by the edge below.
 //
+//  Unsafe because it dereferences the raw NonNull<CEdge> pointer the
+//  active-edge list is built from, same as AdvanceDDAOneEdgeScalar.
+//
 //-------------------------------------------------------------------------
-fn
+unsafe fn
 ComputeDeltaUpperBound(
     pEdge: NonNull<CEdge>,  // Edge containing 1/m value used for computation
     nSubpixelYAdvance: INT          // Multiplier in synopsis expression
@@ -274,13 +359,13 @@ ComputeDeltaUpperBound(
     // Compute the delta bound
     //
 
-    if (pEdge.ErrorUp == 0)
+    if (pEdge.as_ref().ErrorUp == 0)
     {
         //
         // No errorUp, so simply compute bound based on dx value
         //
 
-        nSubpixelDeltaUpperBound = nSubpixelYAdvance*abs(pEdge.Dx);
+        nSubpixelDeltaUpperBound = nSubpixelYAdvance*abs(pEdge.as_ref().Dx);
     }
     else
     {
@@ -293,12 +378,12 @@ ComputeDeltaUpperBound(
         // Here, we can assume errorUp > 0
         //
 
-        assert!(pEdge.ErrorUp > 0);
+        assert!(pEdge.as_ref().ErrorUp > 0);
 
-        if (pEdge.Dx >= 0)
+        if (pEdge.as_ref().Dx >= 0)
         {
-            nAbsDx = pEdge.Dx;
-            nAbsErrorUp = pEdge.ErrorUp;
+            nAbsDx = pEdge.as_ref().Dx;
+            nAbsErrorUp = pEdge.as_ref().ErrorUp;
         }
         else
         {
@@ -310,8 +395,8 @@ ComputeDeltaUpperBound(
             // also means substracting one from dx.
             //
 
-            nAbsDx = -pEdge.Dx - 1;
-            nAbsErrorUp = -pEdge.ErrorUp + pEdge.ErrorDown;
+            nAbsDx = -pEdge.as_ref().Dx - 1;
+            nAbsErrorUp = -pEdge.as_ref().ErrorUp + pEdge.as_ref().ErrorDown;
         }
 
         //
@@ -320,7 +405,7 @@ ComputeDeltaUpperBound(
         // Note that the +1 below is included to bound any left over errorUp that we are dropping here.
         //
 
-        nSubpixelDeltaUpperBound = nSubpixelYAdvance*nAbsDx + (nSubpixelYAdvance*nAbsErrorUp)/pEdge.ErrorDown + 1;
+        nSubpixelDeltaUpperBound = nSubpixelYAdvance*nAbsDx + (nSubpixelYAdvance*nAbsErrorUp)/pEdge.as_ref().ErrorDown + 1;
     }
 
     return nSubpixelDeltaUpperBound;
@@ -334,8 +419,11 @@ ComputeDeltaUpperBound(
 //     Compute some value that is <= distance between
 //     (pEdgeLeft->X, pEdgeLeft->Error) and (pEdgeRight->X, pEdgeRight->Error)
 //
+//  Unsafe because it dereferences the raw NonNull<CEdge> pointers the
+//  active-edge list is built from, same as AdvanceDDAOneEdgeScalar.
+//
 //-------------------------------------------------------------------------
-fn
+unsafe fn
 ComputeDistanceLowerBound(
     pEdgeLeft: NonNull<CEdge>, // Left edge containing the position for the distance computation
     pEdgeRight: NonNull<CEdge> // Right edge containing the position for the distance computation
@@ -359,11 +447,11 @@ ComputeDistanceLowerBound(
     // This case occurs often in thin strokes, so we check for it here.
     //
 
-    assert!(pEdgeLeft.Error  < 0);
-    assert!(pEdgeRight.Error < 0);
-    assert!(pEdgeLeft.X <= pEdgeRight.X);
+    assert!(pEdgeLeft.as_ref().Error  < 0);
+    assert!(pEdgeRight.as_ref().Error < 0);
+    assert!(pEdgeLeft.as_ref().X <= pEdgeRight.as_ref().X);
 
-    let nSubpixelXDistanceLowerBound: INT = pEdgeRight.X - pEdgeLeft.X;
+    let nSubpixelXDistanceLowerBound: INT = pEdgeRight.as_ref().X - pEdgeLeft.as_ref().X;
 
     //
     // If error2/errorDown2 < error1/errorDown1, we need to subtract one from the bound.
@@ -372,10 +460,10 @@ ComputeDistanceLowerBound(
     //
 
     if (IsFractionLessThan(
-             pEdgeRight.Error+1,
-             pEdgeRight.ErrorDown,
-             pEdgeLeft.Error+1,
-             pEdgeLeft.ErrorDown
+             pEdgeRight.as_ref().Error+1,
+             pEdgeRight.as_ref().ErrorDown,
+             pEdgeLeft.as_ref().Error+1,
+             pEdgeLeft.as_ref().ErrorDown
         ))
     {
             // We can't use the tighter lower bound described above, so we need to subtract one to
@@ -386,26 +474,273 @@ ComputeDistanceLowerBound(
 
     return nSubpixelXDistanceLowerBound;
 }
+//-------------------------------------------------------------------------
+//
+//  Enum:       RasterizationMode
+//
+//  Synopsis:
+//      Selects which backend CHwRasterizer::RasterizePath dispatches to.
+//      Trapezoidal is the original scanline/DDA sweep implemented below;
+//      EdgeFunction is the half-space edge-function triangle rasterizer
+//      (see RasterizeTrianglesEdgeFunction near the bottom of this file).
+//
+//-------------------------------------------------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum RasterizationMode
+{
+    Trapezoidal,
+    EdgeFunction,
+}
+
+//-------------------------------------------------------------------------
+//
+//  Enum:       SamplePattern
+//
+//  Synopsis:
+//      Multisample pattern used to resolve coverage, borrowing the
+//      sample-pattern concept from SWR's multisample.cpp.  Each variant
+//      carries a fixed, standard rotated-grid set of sample offsets
+//      within the unit pixel.  The trapezoid/complex-scan sweep in
+//      RasterizePath keeps the fixed c_nShift/c_nShiftSize 8x8
+//      (SamplePattern::X8) grid whichever pattern is selected; the pattern
+//      is read by ComputeCoverageFromSamplePattern, GetVerticalSampleCount
+//      and the edge-flag accumulator's sample count.
+//
+//-------------------------------------------------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum SamplePattern
+{
+    X1,
+    X2,
+    X4,
+    X8,
+    X16,
+}
+
+impl SamplePattern {
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   SamplePattern::SampleCount
+    //
+    //  Synopsis:
+    //      Number of samples N in this pattern.  Coverage for a pixel is
+    //      (covered_samples * 255 / N).
+    //
+    //-------------------------------------------------------------------------
+    fn SampleCount(&self) -> UINT
+    {
+        return match self {
+            SamplePattern::X1  => 1,
+            SamplePattern::X2  => 2,
+            SamplePattern::X4  => 4,
+            SamplePattern::X8  => 8,
+            SamplePattern::X16 => 16,
+        };
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   SamplePattern::Shift
+    //
+    //  Synopsis:
+    //      log2(SampleCount()), i.e. the shift N such that 1 << N == the
+    //      sample count, used in place of the formerly fixed
+    //      c_nShift/c_nShiftSize == 8 subpixel shift; e.g. X8 keeps the
+    //      original 8x8 overscale (Shift() == 3, 1 << 3 == 8), while X1
+    //      collapses to single-sample (no AA, Shift() == 0).
+    //
+    //-------------------------------------------------------------------------
+    fn Shift(&self) -> UINT
+    {
+        return match self {
+            SamplePattern::X1  => 0,
+            SamplePattern::X2  => 1,
+            SamplePattern::X4  => 2,
+            SamplePattern::X8  => 3,
+            SamplePattern::X16 => 4,
+        };
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   SamplePattern::Offsets
+    //
+    //  Synopsis:
+    //      The standard D3D rotated-grid (x, y) sample offsets, each in
+    //      [0, 1), for this pattern.  ComputeCoverageFromSamplePattern
+    //      tests each offset's x against a scanline span.
+    //
+    //-------------------------------------------------------------------------
+    fn Offsets(&self) -> &'static [(f32, f32)]
+    {
+        return match self {
+            SamplePattern::X1  => &[(0.5, 0.5)],
+            SamplePattern::X2  => &[(0.25, 0.25), (0.75, 0.75)],
+            SamplePattern::X4  => &[(0.375, 0.125), (0.875, 0.375), (0.125, 0.625), (0.625, 0.875)],
+            SamplePattern::X8  => &[
+                (0.5625, 0.3125), (0.4375, 0.6875), (0.8125, 0.5625), (0.3125, 0.1875),
+                (0.1875, 0.8125), (0.0625, 0.4375), (0.6875, 0.9375), (0.9375, 0.0625),
+                ],
+            SamplePattern::X16 => &[
+                (0.5625, 0.5625), (0.4375, 0.3125), (0.3125, 0.6250), (0.7500, 0.4375),
+                (0.1875, 0.3750), (0.6250, 0.8125), (0.8125, 0.6875), (0.6875, 0.1875),
+                (0.3750, 0.8750), (0.5000, 0.0625), (0.2500, 0.1250), (0.1250, 0.7500),
+                (0.0000, 0.5000), (0.9375, 0.2500), (0.8750, 0.9375), (0.0625, 0.0000),
+                ],
+        };
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Struct:     CGammaTable
+//
+//  Synopsis:
+//      Precomputed 256-entry lookup table mapping linear fractional
+//      coverage (the [0, 255] byte FillEdgesAlternating/FillEdgesWinding
+//      produce) through a gamma transfer curve, so coverage/reconstruction
+//      happens in the destination's color space instead of linear space --
+//      the fix the OpenVG reference rasterizer uses for the classic
+//      "too thin / too dark" antialiased edge on an sRGB target.  Built
+//      once by Build() and reused until the gamma value changes; rgTable[0]
+//      and rgTable[255] are pinned to 0 and 255 so fully-uncovered and
+//      fully-covered pixels are always exact regardless of rGamma.
+//
+//-------------------------------------------------------------------------
+struct CGammaTable {
+    rgTable: [BYTE; 256],
+    rGamma: f32,
+}
+
+impl CGammaTable {
+    fn new() -> Self
+    {
+        let mut table = CGammaTable { rgTable: [0; 256], rGamma: 1.0 };
+        table.Build(1.0);
+        return table;
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CGammaTable::Build
+    //
+    //  Synopsis:
+    //      Rebuild rgTable for rGamma.  rGamma == 1.0 is the identity
+    //      (linear, pre-existing behavior); c_rSRGBGamma approximates the
+    //      sRGB transfer curve.  Intermediate levels are computed as
+    //      (coverage/255)^(1/rGamma), which keeps the table monotonic for
+    //      any rGamma > 0 so partial coverage never reorders.
+    //
+    //-------------------------------------------------------------------------
+    fn Build(&mut self, rGamma: f32)
+    {
+        self.rGamma = rGamma;
+
+        self.rgTable[0] = 0;
+        self.rgTable[255] = 255;
+
+        for nCoverage in 1..255
+        {
+            let rCoverage = (nCoverage as f32) / 255.0;
+            let rCorrected = rCoverage.powf(1.0 / rGamma);
+            self.rgTable[nCoverage] = (rCorrected * 255.0 + 0.5) as BYTE;
+        }
+    }
+
+    fn Map(&self, nCoverage: BYTE) -> BYTE
+    {
+        return self.rgTable[nCoverage as usize];
+    }
+}
+
+// Standard approximation of the sRGB transfer curve's effective gamma;
+// used by SetGammaCorrection's sRGB convenience overload instead of the
+// true piecewise sRGB curve, matching the precision the rest of this
+// crate's coverage math already works in.
+const c_rSRGBGamma: f32 = 2.2;
+
+//-------------------------------------------------------------------------
+//
+//  Enum:       ConservativeMode
+//
+//  Synopsis:
+//      Off is the ordinary antialiased result.  Outer reinterprets
+//      accumulated coverage so any pixel the path even grazes (nonzero
+//      coverage) is reported as fully covered -- a strict superset of the
+//      normal AA footprint, useful for occlusion/culling masks and tile
+//      binning.  Inner is the dual: only pixels that are fully interior
+//      (coverage == FULL across every subpixel scanline) are reported,
+//      a strict subset of the AA footprint.  Supersedes the plain on/off
+//      conservative-raster flag this crate started with -- that behavior
+//      is exactly what Outer now names.
+//
+//-------------------------------------------------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum ConservativeMode
+{
+    Off,
+    Outer,
+    Inner,
+}
+
 struct CHwRasterizer {
-    m_pIGeometrySink: Rc<IGeometrySink>,
+    m_pIGeometrySink: Rc<dyn IGeometrySink>,
     m_prgPoints: Option<&mut Vec<MilPoint2F>>,
     m_prgTypes: Option<&mut Vec<BYTE>>,
-    /* 
-DynArray<MilPoint2F> *m_prgPoints;
-DynArray<BYTE>       *m_prgTypes;
-MilPointAndSizeL      m_rcClipBounds;
-CMILMatrix            m_matWorldToDevice;
-IGeometrySink        *m_pIGeometrySink;
-MilFillMode::Enum     m_fillMode;
+    m_rasterizationMode: RasterizationMode,
+    m_samplePattern: SamplePattern,
+    m_conservativeMode: ConservativeMode,
+    m_coverageAccumulationMode: CoverageAccumulationMode,
+    m_coverageOutputMode: CoverageOutputMode,
+    m_fGammaCorrection: bool,
+    m_gammaTable: CGammaTable,
+    m_fPreventDropout: bool,
+    m_rcClipBounds: MilPointAndSizeL,
+    m_matWorldToDevice: CMILMatrix,
+    m_fillMode: MilFillMode,
+
+    // Complex scan coverage buffer
+    m_coverageBuffer: CCoverageBuffer,
+
+    // Alternate complex-scan accumulators selected by
+    // SetCoverageAccumulationMode/m_coverageAccumulationMode; see
+    // GenerateOutputAndClearCoverage.
+    m_cellCoverageAccumulator: CCellCoverageAccumulator,
+    m_edgeFlagBitmaskAccumulator: CEdgeFlagBitmaskAccumulator,
+
+    m_pDeviceNoRef: Option<Rc<CD3DDeviceLevel1>>,
+}
 
+//-------------------------------------------------------------------------
+//
+//  Struct:     CNullGeometrySink
 //
-// Complex scan coverage buffer
+//  Synopsis:
+//      Placeholder m_pIGeometrySink for a freshly-constructed
+//      CHwRasterizer, before Setup/SendGeometry ever supplies the real
+//      sink for a given fill call.  Every call is a programming error
+//      (nothing should route output through it), so every method reports
+//      E_NOTIMPL rather than silently dropping geometry.
 //
+//-------------------------------------------------------------------------
+struct CNullGeometrySink;
 
-CCoverageBuffer m_coverageBuffer;
+impl IGeometrySink for CNullGeometrySink {
+    fn AddComplexScan(&mut self, _nPixelY: INT, _pIntervalSpanStart: &CCoverageInterval) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
 
-CD3DDeviceLevel1 * m_pDeviceNoRef;*/
+    fn AddTrapezoid(&mut self,
+        _rPixelYTop: f32, _rPixelXTopLeft: f32, _rPixelXTopRight: f32,
+        _rPixelYBottom: f32, _rPixelXBottomLeft: f32, _rPixelXBottomRight: f32,
+        _rPixelXLeftDelta: f32, _rPixelXRightDelta: f32
+        ) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
 }
+
 impl CHwRasterizer {
 //-------------------------------------------------------------------------
 //
@@ -416,10 +751,151 @@ impl CHwRasterizer {
 //-------------------------------------------------------------------------
 fn new() -> Self
 {
-    m_pDeviceNoRef = NULL;
+    let mut matWorldToDevice = CMILMatrix::default();
+    matWorldToDevice.SetToIdentity();
+
+    return Self {
+        m_pIGeometrySink: Rc::new(CNullGeometrySink),
+        m_prgPoints: None,
+        m_prgTypes: None,
+        m_rasterizationMode: RasterizationMode::Trapezoidal,
+
+        // X8 matches the historical hard-coded 8x8 overscale, so existing
+        // callers that never touch SetSamplePattern see no behavior change.
+        m_samplePattern: SamplePattern::X8,
+        m_conservativeMode: ConservativeMode::Off,
+        m_coverageAccumulationMode: CoverageAccumulationMode::Supersampled8x8,
+
+        // Resolved by default: existing callers/sinks keep seeing one
+        // coverage byte per pixel, bit-for-bit identical to before this
+        // mode existed.
+        m_coverageOutputMode: CoverageOutputMode::Resolved,
+
+        // Off by default: gamma == 1.0 is the identity table, so this is
+        // a no-op until a caller opts in via SetGammaCorrection.
+        m_fGammaCorrection: false,
+        m_gammaTable: CGammaTable::new(),
+
+        // Off by default: existing callers get bit-identical output to
+        // before this feature existed.  See SetPreventDropout.
+        m_fPreventDropout: false,
+
+        // Cleared for real on the Setup call.
+        m_rcClipBounds: MilPointAndSizeL::default(),
+        m_fillMode: MilFillMode::Alternate,
+        m_coverageBuffer: CCoverageBuffer::default(),
+        // Reseeded for the real (nXMin, nXMax) bounds on each scanline by
+        // GenerateOutputAndClearCoverage's callers; (0, 0) just gives the
+        // freshly-constructed rasterizer a valid single-column buffer.
+        m_cellCoverageAccumulator: CCellCoverageAccumulator::new(0, 0),
+        m_edgeFlagBitmaskAccumulator: CEdgeFlagBitmaskAccumulator::new(0, 0, SamplePattern::X8.SampleCount()),
+        m_pDeviceNoRef: None,
+
+        m_matWorldToDevice: matWorldToDevice,
+    };
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetRasterizationMode
+//
+//  Synopsis:
+//      Choose the backend used by RasterizePath.  EdgeFunction trades the
+//      trapezoid/complex-scan sweep for the tile-parallel half-space
+//      rasterizer; callers that need a fill rule the edge-function path
+//      can't handle (see RasterizeTrianglesEdgeFunction) will silently
+//      fall back to Trapezoidal for that call.
+//
+//-------------------------------------------------------------------------
+fn SetRasterizationMode(&mut self, mode: RasterizationMode)
+{
+    self.m_rasterizationMode = mode;
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetSamplePattern
+//
+//  Synopsis:
+//      Select the multisample pattern used to resolve coverage, trading
+//      quality for speed or matching a render target's MSAA level.  Must
+//      be called before Setup.  ConvertSubpixelXToPixel/
+//      ConvertSubpixelYToPixel and the sweep in RasterizePath keep the
+//      fixed c_nShift/c_nShiftSize == 8 grid; the pattern sets what
+//      ComputeCoverageFromSamplePattern counts, what
+//      GetVerticalSampleCount reports, and the edge-flag accumulator's
+//      sample count S (the accumulator is rebuilt, keeping its column
+//      range).  Also rebuilds m_gammaTable with its current gamma.
+//
+//-------------------------------------------------------------------------
+fn SetSamplePattern(&mut self, pattern: SamplePattern)
+{
+    self.m_samplePattern = pattern;
+    let nXMin = self.m_edgeFlagBitmaskAccumulator.nXMin;
+    let nXMax = nXMin + (self.m_edgeFlagBitmaskAccumulator.rgMask.len() as INT) - 1;
+    self.m_edgeFlagBitmaskAccumulator = CEdgeFlagBitmaskAccumulator::new(nXMin, nXMax, pattern.SampleCount());
+    self.m_gammaTable.Build(self.m_gammaTable.rGamma);
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetConservativeMode
+//
+//  Synopsis:
+//      Select conservative-raster output (see ConservativeMode).  This
+//      coexists with fill mode (Alternate/Winding) selection and is
+//      valuable for clip-region generation, GPU occlusion pre-passes, and
+//      pointer hit-testing/tile binning, where "does the shape touch this
+//      pixel at all" (Outer) or "is this pixel fully interior" (Inner)
+//      matters more than AA quality.
+//
+//      Outer affects both rasterization paths: GenerateOutputAndClearCoverage
+//      promotes any nonzero complex-scan interval to full coverage, and
+//      OutputTrapezoids replaces the falloff expand distance with integer
+//      outward snapping of the trapezoid's top/bottom X bounds.  Inner is
+//      resolved only in GenerateOutputAndClearCoverage, since the
+//      trapezoid path by construction never emits a trapezoid whose
+//      interior isn't already fully covered. Either non-Off setting
+//      bypasses the AA color source setup in SendGeometryModifiers, since
+//      there's no fractional coverage left to modulate by.
+//
+//-------------------------------------------------------------------------
+fn SetConservativeMode(&mut self, mode: ConservativeMode)
+{
+    self.m_conservativeMode = mode;
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::ComputeCoverageFromSamplePattern
+//
+//  Synopsis:
+//      Resolve a pixel's coverage by counting how many of the configured
+//      pattern's sample positions fall inside the [rPixelXLeft, rPixelXRight)
+//      span produced for this scanline, rather than assuming the uniform
+//      8-row subpixel sweep AdvanceDDAMultipleSteps was originally tuned
+//      for.  Coverage is returned in the same [0, 255] range the rest of
+//      the rasterizer already expects from CCoverageInterval.
+//
+//-------------------------------------------------------------------------
+fn ComputeCoverageFromSamplePattern(&self,
+    rPixelXLeft: f32,
+    rPixelXRight: f32
+    ) -> BYTE
+{
+    let rgOffsets = self.m_samplePattern.Offsets();
+    let nCount = self.m_samplePattern.SampleCount();
+
+    let mut nCovered: UINT = 0;
+    for (rSampleX, _rSampleY) in rgOffsets
+    {
+        if (*rSampleX >= rPixelXLeft) && (*rSampleX < rPixelXRight)
+        {
+            nCovered += 1;
+        }
+    }
 
-    // State is cleared on the Setup call
-    m_matWorldToDevice.SetToIdentity();
+    return ((nCovered * 255) / nCount) as BYTE;
 }
 
 //-------------------------------------------------------------------------
@@ -430,6 +906,11 @@ fn new() -> Self
 //      Convert from our subpixel coordinate (x + error/errorDown)
 //      to a floating point value.
 //
+//      Note: this always uses the fixed c_nShift/c_rInvShiftSize grid
+//      resolution, whatever m_samplePattern is;
+//      ComputeCoverageFromSamplePattern works from the pattern's
+//      Offsets()/SampleCount() instead of this helper.
+//
 //-------------------------------------------------------------------------
 fn ConvertSubpixelXToPixel(
     x: INT,
@@ -483,33 +964,53 @@ fn ConvertSubpixelYToPixel(
 //
 //-------------------------------------------------------------------------
 fn RasterizePath(
+    pRasterizer: &mut CHwRasterizer,
     rgpt: &[MilPoint2F],
     rgTypes: &[BYTE],
     cPoints: UINT,
     pmatWorldTransform: &CMILMatrix,
     fillMode: MilFillMode
-    ) -> HERSULT
+    ) -> HRESULT
 {
     let mut hr = S_OK;
     let inactiveArrayStack: [CInactiveEdge; INACTIVE_LIST_NUMBER];
-    CInactiveEdge *pInactiveArray;
-    CInactiveEdge *pInactiveArrayAllocation = NULL;
-    let edgeHead: CEdge;
-    let edgeTail: CEdge;
-    let pEdgeActiveList: *const CEdge;
+    let mut pInactiveArray: *mut CInactiveEdge;
+    let mut pInactiveArrayAllocation: *mut CInactiveEdge = std::ptr::null_mut();
+
+    // Terminator to active/inactive list.  CEdge is a singly-linked list
+    // node, so the tail sentinel has to exist before the head sentinel's
+    // Next can point at it; NonNull::dangling() is safe here because a
+    // well-formed walk stops at EndY == i32::MIN before ever reading
+    // edgeTail.Next.
+    let edgeTail: CEdge = CEdge {
+        X: i32::MAX,
+        StartY: i32::MAX,
+        EndY: i32::MIN,
+        WindingDirection: 0,
+        Dx: 0,
+        ErrorUp: 0,
+        ErrorDown: 0,
+        Error: 0,
+        Next: NonNull::dangling(),
+    };
+    let edgeHead: CEdge = CEdge {
+        X: i32::MIN,       // Beginning of active list
+        StartY: 0,
+        EndY: 0,
+        WindingDirection: 0,
+        Dx: 0,
+        ErrorUp: 0,
+        ErrorDown: 0,
+        Error: 0,
+        Next: NonNull::from(&edgeTail),
+    };
+    let mut pEdgeActiveList: *const CEdge;
     let edgeStore: CEdgeStore;
-    let edgeContext: CInitializeEdgesContext;
-
-    edgeContext.ClipRect = NULL;
+    let mut edgeContext: CInitializeEdgesContext;
 
-    edgeTail.X = i32::MAX;       // Terminator to active list
-    edgeTail.StartY = i32::MAX;  // Terminator to inactive list
-
-    edgeTail.EndY = i32::MIN;
-    edgeHead.X = i32::MIN;       // Beginning of active list
+    edgeContext.ClipRect = std::ptr::null();
     edgeContext.MaxY = i32::MIN;
 
-    edgeHead.Next = &edgeTail;
     pEdgeActiveList = &edgeHead;
     edgeContext.Store = &edgeStore;
 
@@ -522,16 +1023,34 @@ fn RasterizePath(
         return S_OK;
     }
 
-    let nPixelYClipBottom: INT = m_rcClipBounds.Y + m_rcClipBounds.Height;
+    //
+    // If the edge-function backend was requested, tessellate into triangles
+    // and hand off to it.  It only understands the Alternate fill rule over
+    // a triangle list (see RasterizeTrianglesEdgeFunction); anything else
+    // falls back to the trapezoidal sweep below.
+    //
+
+    if (pRasterizer.m_rasterizationMode == RasterizationMode::EdgeFunction
+        && fillMode == MilFillMode::Alternate)
+    {
+        return pRasterizer.RasterizeTrianglesEdgeFunction(
+            rgpt,
+            rgTypes,
+            cPoints,
+            pmatWorldTransform
+            );
+    }
+
+    let nPixelYClipBottom: INT = pRasterizer.m_rcClipBounds.Y + pRasterizer.m_rcClipBounds.Height;
 
     // Scale the clip bounds rectangle by 16 to account for our
     // scaling to 28.4 coordinates:
 
-    let clipBounds : RECT;
-    clipBounds.left   = m_rcClipBounds.X * FIX4_ONE;
-    clipBounds.top    = m_rcClipBounds.Y * FIX4_ONE;
-    clipBounds.right  = (m_rcClipBounds.X + m_rcClipBounds.Width) * FIX4_ONE;
-    clipBounds.bottom = (m_rcClipBounds.Y + m_rcClipBounds.Height) * FIX4_ONE;
+    let mut clipBounds : RECT;
+    clipBounds.left   = pRasterizer.m_rcClipBounds.X * FIX4_ONE;
+    clipBounds.top    = pRasterizer.m_rcClipBounds.Y * FIX4_ONE;
+    clipBounds.right  = (pRasterizer.m_rcClipBounds.X + pRasterizer.m_rcClipBounds.Width) * FIX4_ONE;
+    clipBounds.bottom = (pRasterizer.m_rcClipBounds.Y + pRasterizer.m_rcClipBounds.Height) * FIX4_ONE;
 
     edgeContext.ClipRect = &clipBounds;
 
@@ -543,14 +1062,16 @@ fn RasterizePath(
 
     // Enumerate the path and construct the edge table:
 
-    MIL_THR!(FixedPointPathEnumerate(
+    'cleanup: loop {
+
+    hr = FixedPointPathEnumerate(
         rgpt,
         rgTypes,
         cPoints,
         &matrix,
         edgeContext.ClipRect,
         &edgeContext
-        ));
+        );
 
     if (FAILED(hr))
     {
@@ -559,14 +1080,14 @@ fn RasterizePath(
             // Draw nothing on value overflow and return
             hr = S_OK;
         }
-        goto Cleanup;
+        break 'cleanup;
     }
 
-    let nTotalCount: UINT; nTotalCount = edgeStore.StartEnumeration();
+    let nTotalCount: UINT = edgeStore.StartEnumeration();
     if (nTotalCount == 0)
     {
         hr = S_OK;     // We're outta here (empty path or entirely clipped)
-        goto Cleanup;
+        break 'cleanup;
     }
 
     // At this point, there has to be at least two edges.  If there's only
@@ -574,35 +1095,39 @@ fn RasterizePath(
 
     assert!((nTotalCount >= 2) && (nTotalCount <= (UINT_MAX - 2)));
 
-    pInactiveArray = &inactiveArrayStack[0];
+    pInactiveArray = &mut inactiveArrayStack[0];
     if (nTotalCount > (INACTIVE_LIST_NUMBER - 2))
     {
-        IFC!(HrMalloc(
+        hr = HrMalloc(
             Mt(HwRasterizerEdge),
             sizeof(CInactiveEdge),
             nTotalCount + 2,
             (void **)&pInactiveArrayAllocation
-            ));
+            );
+        if (FAILED(hr))
+        {
+            break 'cleanup;
+        }
 
         pInactiveArray = pInactiveArrayAllocation;
     }
 
     // Initialize and sort the inactive array:
 
-    INT nSubpixelYCurrent; nSubpixelYCurrent = InitializeInactiveArray(
+    let nSubpixelYCurrent: INT = InitializeInactiveArray(
         &edgeStore,
         pInactiveArray,
         nTotalCount,
         &edgeTail
         );
 
-    let nSubpixelYBottom = edgeContext.MaxY;
+    let mut nSubpixelYBottom = edgeContext.MaxY;
 
     assert!(nSubpixelYBottom > 0);
 
     // Skip the head sentinel on the inactive array:
 
-    pInactiveArray += 1;
+    pInactiveArray = pInactiveArray.offset(1);
 
     //
     // Rasterize the path
@@ -619,19 +1144,22 @@ fn RasterizePath(
 
     assert!(nSubpixelYBottom > nSubpixelYCurrent);
 
-    IFC(RasterizeEdges(
+    hr = RasterizeEdges(
         pEdgeActiveList,
         pInactiveArray,
         nSubpixelYCurrent,
         nSubpixelYBottom
-        ));
+        );
+
+    break 'cleanup;
+
+    } // 'cleanup: loop
 
-Cleanup:
     // Free any objects and get outta here:
     GpFree(pInactiveArrayAllocation);
 
     // Free coverage buffer
-    m_coverageBuffer.Destroy();
+    pRasterizer.m_coverageBuffer.Destroy();
 
     return hr;
 }
@@ -731,7 +1259,7 @@ fn Setup(&mut self,
 //
 //-------------------------------------------------------------------------
 fn SendGeometry(&self,
-    pIGeometrySink: Rc<IGeometrySink>
+    pIGeometrySink: Rc<dyn IGeometrySink>
     ) -> HRESULT
 {
     let hr = S_OK;
@@ -748,6 +1276,7 @@ fn SendGeometry(&self,
     //
 
     IFC!(RasterizePath(
+        self,
         m_prgPoints->GetDataBuffer(),
         m_prgTypes->GetDataBuffer(),
         m_prgPoints->GetCount(),
@@ -785,6 +1314,17 @@ fn SendGeometryModifiers(
 {
     HRESULT hr = S_OK;
 
+    //
+    // Conservative raster output is already either fully covered or fully
+    // empty per pixel, so there's no antialiased coverage to modulate by;
+    // skip wiring up the AA color source entirely.
+    //
+
+    if (self.m_conservativeMode != ConservativeMode::Off)
+    {
+        return S_OK;
+    }
+
     CHwColorComponentSource *pAntiAliasColorSource = NULL;
 
     m_pDeviceNoRef->GetColorComponentSource(
@@ -807,25 +1347,124 @@ Cleanup:
 //  Function:   CHwRasterizer::GenerateOutputAndClearCoverage
 //
 //  Synopsis:
-//      Collapse output and generate span data
+//      Collapse output and generate span data.
+//
+//      In ConservativeMode::Outer, any interval that the sweep gave
+//      nonzero (even fractional) coverage is promoted to full coverage
+//      before it's handed to the sink: for hit-testing and occlusion
+//      purposes, a pixel the shape merely grazes must still read as
+//      "covered".  In ConservativeMode::Inner, the opposite: only
+//      intervals whose coverage already equals the maximum possible for
+//      this accumulation (i.e. every subpixel scanline summed into this
+//      pixel row was "inside") survive; anything partially covered is
+//      zeroed.  The two are careful to use the exact "touched"/"fully
+//      filled" boundaries rather than rounding, so Outer is always a
+//      strict superset of the normal AA footprint and Inner a strict
+//      subset.  Left/right span bounds are not otherwise adjusted here --
+//      the floor/ceil-to-whole-pixel snapping for Outer happens where the
+//      span is produced, i.e. in the AdvanceDDAMultipleSteps consumers
+//      upstream (OutputTrapezoids for the trapezoid path).
+//
+//      In CoverageOutputMode::PerSample, the conservative-mode promotion/
+//      filtering above is skipped -- it only makes sense once coverage has
+//      been collapsed to a single resolved byte -- and the coverage
+//      buffer's raw per-sample bitmask is handed to the sink instead of
+//      the resolved interval list, via AddComplexScanSampleMask.  A
+//      consumer that wants the resolved byte back just popcounts the mask
+//      and scales by 255/SampleCount(), the same arithmetic
+//      ComputeCoverageFromSamplePattern already does, so Resolved and
+//      PerSample agree bit-for-bit on what a pixel means.
+//
+//      When m_fGammaCorrection is set, every interval's linear coverage
+//      byte is remapped through m_gammaTable right before the sink call,
+//      after conservative promotion/filtering -- those already collapse
+//      coverage to 0 or 255, which m_gammaTable maps to themselves, so
+//      the order doesn't matter for them, but applying gamma first would
+//      have nonzero-coverage intervals disagree with a literal "promote
+//      to full" reading of ConservativeMode::Outer.
 //
 //-------------------------------------------------------------------------
-MIL_FORCEINLINE HRESULT
-CHwRasterizer::GenerateOutputAndClearCoverage(
-    INT nSubpixelY
-    )
+fn GenerateOutputAndClearCoverage(
+    &mut self,
+    nSubpixelY: INT
+    ) -> HRESULT
 {
-    HRESULT hr = S_OK;
-    INT nPixelY = nSubpixelY >> c_nShift;
+    let mut hr = S_OK;
+    let nPixelY: INT = nSubpixelY >> c_nShift;
+    let pGeometrySink = Rc::get_mut(&mut self.m_pIGeometrySink)
+        .expect("m_pIGeometrySink is not shared while rasterizing");
+
+    'cleanup: loop {
 
-    const CCoverageInterval *pIntervalSpanStart = m_coverageBuffer.m_pIntervalStart;
+    if (self.m_coverageOutputMode == CoverageOutputMode::PerSample)
+    {
+        let pSampleMaskSpanStart: &CCoverageInterval = self.m_coverageBuffer.m_pIntervalStart;
 
-    IFC(m_pIGeometrySink->AddComplexScan(nPixelY, pIntervalSpanStart));
+        hr = pGeometrySink.AddComplexScanSampleMask(
+            nPixelY,
+            pSampleMaskSpanStart
+            );
 
-    m_coverageBuffer.Reset();
+        self.m_coverageBuffer.Reset();
 
-Cleanup:
-    RRETURN(hr);
+        break 'cleanup;
+    }
+
+    if (self.m_coverageAccumulationMode == CoverageAccumulationMode::CellCoverage)
+    {
+        let mut rgCoverage: Vec<BYTE> = vec![0; self.m_cellCoverageAccumulator.rgCover.len()];
+        self.m_cellCoverageAccumulator.Resolve(&mut rgCoverage);
+
+        hr = pGeometrySink.AddComplexScanCoverageBytes(
+            nPixelY,
+            self.m_cellCoverageAccumulator.nXMin,
+            &rgCoverage
+            );
+
+        self.m_cellCoverageAccumulator.Reset();
+
+        break 'cleanup;
+    }
+
+    if (self.m_coverageAccumulationMode == CoverageAccumulationMode::EdgeFlagBitmask)
+    {
+        let mut rgCoverage: Vec<BYTE> = vec![0; self.m_edgeFlagBitmaskAccumulator.rgMask.len()];
+        self.m_edgeFlagBitmaskAccumulator.Resolve(&mut rgCoverage);
+
+        hr = pGeometrySink.AddComplexScanCoverageBytes(
+            nPixelY,
+            self.m_edgeFlagBitmaskAccumulator.nXMin,
+            &rgCoverage
+            );
+
+        self.m_edgeFlagBitmaskAccumulator.Reset();
+
+        break 'cleanup;
+    }
+
+    match self.m_conservativeMode
+    {
+        ConservativeMode::Off    => {},
+        ConservativeMode::Outer  => self.m_coverageBuffer.PromoteNonzeroIntervalsToFull(),
+        ConservativeMode::Inner  => self.m_coverageBuffer.KeepOnlyFullyCoveredIntervals(),
+    }
+
+    if (self.m_fGammaCorrection)
+    {
+        self.m_coverageBuffer.ApplyCoverageLut(&self.m_gammaTable.rgTable);
+    }
+
+    let pIntervalSpanStart: &CCoverageInterval = self.m_coverageBuffer.m_pIntervalStart;
+
+    hr = pGeometrySink.AddComplexScan(nPixelY, pIntervalSpanStart);
+
+    self.m_coverageBuffer.Reset();
+
+    break 'cleanup;
+
+    } // 'cleanup: loop
+
+    RRETURN(hr)
 }
 
 //-------------------------------------------------------------------------
@@ -872,6 +1511,43 @@ Cleanup:
 //
 //-------------------------------------------------------------------------
 
+//-------------------------------------------------------------------------
+//
+//  Function:   WindingBoundaryRight
+//
+//  Synopsis:
+//      Given pEdgeLeft, an edge at which the running nonzero-winding count
+//      transitions from zero to nonzero, walk forward accumulating each
+//      edge's WindingDirection and return the edge at which the count
+//      returns to zero -- the true right edge of the trapezoid bounded by
+//      this "inside" run.  For simple alternating geometry this is always
+//      pEdgeLeft->Next (a single +1/-1 pair), but self-intersecting or
+//      overlapping sub-paths can stack same-direction edges before the
+//      count unwinds back to zero, in which case the interior edges are
+//      skipped over entirely rather than treated as separate pairs.
+//
+//-------------------------------------------------------------------------
+fn
+WindingBoundaryRight(
+    pEdgeLeft: NonNull<CEdge>
+    ) -> NonNull<CEdge>
+{
+    let mut nWindingCount = unsafe { pEdgeLeft.as_ref().WindingDirection };
+    let mut pEdge = unsafe { pEdgeLeft.as_ref().Next };
+
+    while (nWindingCount != 0 && unsafe { pEdge.as_ref().EndY } != INT_MIN)
+    {
+        nWindingCount += unsafe { pEdge.as_ref().WindingDirection };
+        if (nWindingCount == 0)
+        {
+            break;
+        }
+        pEdge = unsafe { pEdge.as_ref().Next };
+    }
+
+    return pEdge;
+}
+
 fn ComputeTrapezoidsEndScan(
     __in_ecount(1) const CEdge *pEdgeCurrent,
     INT nSubpixelYCurrent,
@@ -889,44 +1565,29 @@ fn ComputeTrapezoidsEndScan(
     Assert((nSubpixelYCurrent & c_nShiftMask) == 0);
 
     //
-    // If we are doing a winding mode fill, check that we can ignore mode and do an
-    // alternating fill in OutputTrapezoids.  This condition occurs when winding is
-    // equivalent to alternating which happens if the pairwise edges have different
-    // winding directions.
+    // For each edge, we:
+    //
+    //    1. Set the new trapezoid bottom to the min of the current
+    //       one and the edge EndY
+    //
+    //    2. Check if edges will intersect during trapezoidal shrink/expand
+    //
+    // In Winding mode, the pairwise trapezoid boundaries aren't simply
+    // (pEdge, pEdge->Next) the way they are for Alternate: a run of
+    // same-direction edges can keep the running winding count away from
+    // zero across more than one crossing (self-intersecting or overlapping
+    // sub-paths).  WindingBoundaryRight walks forward from a zero-crossing
+    // start edge, accumulating WindingDirection, and returns the edge where
+    // the count returns to zero.  Those derived (left, right) pairs are
+    // what steps 1/2 below operate on.  A pair that skips over interior
+    // edges gets no trapezoid: the whole scanline falls back to complex
+    // scans (see the pEdgeLeft->Next != pEdgeRight check below), so only
+    // pairs of adjacent edges reach the trapezoid fast path.
     //
 
-    if (m_fillMode == MilFillMode::Winding)
-    {
-        for (const CEdge *pEdge = pEdgeCurrent; pEdge->EndY != INT_MIN; pEdge = pEdge->Next->Next)
-        {
-            // The active edge list always has an even number of edges which we actually
-            // assert in ASSERTACTIVELIST.
-
-            Assert(pEdge->Next->EndY != INT_MIN);
-
-            // If not alternating winding direction, we can't fill with alternate mode
+    nSubpixelYBottomTrapezoids = nSubpixelYNextInactive;
 
-            if (pEdge->WindingDirection == pEdge->Next->WindingDirection)
-            {
-                // Give up until we handle winding mode
-                nSubpixelYBottomTrapezoids = nSubpixelYCurrent;
-                goto Cleanup;
-            }
-        }
-    }
-
-    //
-    // For each edge, we:
-    //
-    //    1. Set the new trapezoid bottom to the min of the current
-    //       one and the edge EndY
-    //
-    //    2. Check if edges will intersect during trapezoidal shrink/expand
-    //
-
-    nSubpixelYBottomTrapezoids = nSubpixelYNextInactive;
-
-    for (const CEdge *pEdge = pEdgeCurrent; pEdge->EndY != INT_MIN; pEdge = pEdge->Next)
+    for (const CEdge *pEdge = pEdgeCurrent; pEdge->EndY != INT_MIN; pEdge = pEdgeRight->Next)
     {
         //
         // Step 1
@@ -946,9 +1607,34 @@ fn ComputeTrapezoidsEndScan(
         //
 
         pEdgeLeft = pEdge;
-        pEdgeRight = pEdge->Next;
+        pEdgeRight = (m_fillMode == MilFillMode::Winding)
+            ? WindingBoundaryRight(pEdgeLeft)
+            : pEdge->Next;
+
+        if (pEdgeRight->EndY == INT_MIN)
+        {
+            // A winding boundary that never closes before the sentinel means
+            // the active list is malformed; fall back to complex scans.
+            nSubpixelYBottomTrapezoids = nSubpixelYCurrent;
+            goto Cleanup;
+        }
+
+        nSubpixelYBottomTrapezoids = min(nSubpixelYBottomTrapezoids, pEdgeRight->EndY);
+
+        // pEdgeRight is now known non-sentinel (checked above).  If the
+        // winding boundary skipped over interior edges to get here (i.e.
+        // pEdgeLeft->Next != pEdgeRight), we have no cheap way to check
+        // that those interior edges stay clear of the left/right
+        // boundary during the shrink/expand below, so don't start a
+        // trapezoid on this scanline; fall back to complex scans instead
+        // of assuming some downstream check will catch it.
+
+        if (pEdgeLeft->Next != pEdgeRight)
+        {
+            nSubpixelYBottomTrapezoids = nSubpixelYCurrent;
+            goto Cleanup;
+        }
 
-        if (pEdgeRight->EndY != INT_MIN)
         {
             //
             //        __A__A'___________________B'_B__
@@ -1196,7 +1882,12 @@ CHwRasterizer::OutputTrapezoids(
     float rPixelXRightDelta;
 
     CEdge *pEdgeLeft = pEdgeCurrent;
-    CEdge *pEdgeRight = pEdgeCurrent->Next;
+    // In Winding mode a trapezoid's right edge is wherever the running
+    // winding count returns to zero, which can skip over same-direction
+    // interior edges; in Alternate mode that's always just the next edge.
+    CEdge *pEdgeRight = (m_fillMode == MilFillMode::Winding)
+        ? WindingBoundaryRight(pEdgeLeft)
+        : pEdgeCurrent->Next;
 
     assert!((nSubpixelYCurrent & c_nShiftMask) == 0);
     assert!(pEdgeLeft->EndY != INT_MIN);
@@ -1256,8 +1947,26 @@ CHwRasterizer::OutputTrapezoids(
         rSubpixelRightInvSlope    = static_cast<float>(pEdgeRight->Dx) + static_cast<float>(pEdgeRight->ErrorUp)/rSubpixelRightErrorDown;
         rSubpixelRightAbsInvSlope = fabsf(rSubpixelRightInvSlope);
 
-        rPixelXLeftDelta  = 0.5f + 0.5f * rSubpixelLeftAbsInvSlope;
-        rPixelXRightDelta = 0.5f + 0.5f * rSubpixelRightAbsInvSlope;
+        if (self.m_conservativeMode == ConservativeMode::Outer)
+        {
+            // Conservative raster: snap outward to whole pixels instead of
+            // the 0.5 + 0.5/m antialiasing falloff, so any pixel even
+            // partially overlapped by the fill is reported with full
+            // coverage.  The falloff region would otherwise only reach
+            // the true pixel boundary in the limit as the interior grows;
+            // rounding rPixelXLeft/rPixelXRight out to the enclosing
+            // integer and zeroing the delta achieves the same "touched
+            // implies covered" guarantee directly.
+            rPixelXLeft  = rPixelXLeft.floor();
+            rPixelXRight = rPixelXRight.ceil();
+            rPixelXLeftDelta  = 0.0;
+            rPixelXRightDelta = 0.0;
+        }
+        else
+        {
+            rPixelXLeftDelta  = 0.5f + 0.5f * rSubpixelLeftAbsInvSlope;
+            rPixelXRightDelta = 0.5f + 0.5f * rSubpixelRightAbsInvSlope;
+        }
 
         float rPixelYTop         = ConvertSubpixelYToPixel(nSubpixelYCurrent);
         float rPixelYBottom      = ConvertSubpixelYToPixel(nSubpixelYNext);
@@ -1274,6 +1983,12 @@ CHwRasterizer::OutputTrapezoids(
                                         static_cast<float>(pEdgeRight->ErrorDown)
                                         );
 
+        if (self.m_conservativeMode == ConservativeMode::Outer)
+        {
+            rPixelXBottomLeft  = rPixelXBottomLeft.floor();
+            rPixelXBottomRight = rPixelXBottomRight.ceil();
+        }
+
         //
         // Output the trapezoid
         //
@@ -1314,7 +2029,9 @@ CHwRasterizer::OutputTrapezoids(
         //
 
         pEdgeLeft  = pEdgeRight->Next;
-        pEdgeRight = pEdgeLeft->Next;
+        pEdgeRight = (m_fillMode == MilFillMode::Winding)
+            ? WindingBoundaryRight(pEdgeLeft)
+            : pEdgeLeft->Next;
 
     }
 
@@ -1455,6 +2172,19 @@ CHwRasterizer::RasterizeEdges(
                 {
                     IFC(m_coverageBuffer.FillEdgesWinding(pEdgeActiveList, nSubpixelYCurrent));
                 }
+
+                //
+                // Guarantee that any contour touching a pixel's center region
+                // yields non-empty output: if the span above collapsed to zero
+                // width within a cell but an edge still transitions through
+                // that cell on this subpixel row, top up its coverage rather
+                // than silently dropping the pixel.
+                //
+
+                if (self.m_fPreventDropout)
+                {
+                    IFC(m_coverageBuffer.EnsureMinimumDropoutCoverage(pEdgeActiveList, nSubpixelYCurrent));
+                }
             }
 
             // If the next scan is done, output what's there:
@@ -1498,4 +2228,1502 @@ Cleanup:
     RRETURN(hr);
 }
 
+} // impl CHwRasterizer
+
+//-------------------------------------------------------------------------
+//
+// Half-space edge-function triangle rasterizer
+//
+//  Synopsis:
+//      Alternative backend to the trapezoidal/complex-scan sweep above.
+//      The path is tessellated into a triangle list up front; each
+//      triangle is then rasterized independently with three affine edge
+//      functions, following the approach used by Intel's software
+//      rasterizer (SWR) RasterizeTriPoint.  Because the edge functions are
+//      affine in X and Y, a tile's edge values can be evaluated once at
+//      the tile's corner and then walked across the tile with pure adds,
+//      which keeps the inner loop branch-light and makes it a natural fit
+//      for tile-parallel coverage evaluation.
+//
+//      This backend only supports the Alternate fill rule; CHwRasterizer
+//      falls back to the trapezoidal path for anything else (see the
+//      dispatch in RasterizePath above).
+//
+//-------------------------------------------------------------------------
+
+//
+// Coefficients for the affine edge function E(x, y) = A*x + B*y + C of the
+// edge from (x0, y0) to (x1, y1), evaluated in 28.4 fixed point.
+//
+struct CEdgeFunction {
+    A: LONGLONG,  // y1 - y0
+    B: LONGLONG,  // x0 - x1
+    C: LONGLONG,  // x1*y0 - x0*y1
+}
+
+impl CEdgeFunction {
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CEdgeFunction::FromPoints
+    //
+    //  Synopsis:
+    //      Compute A, B, C for the edge (x0, y0) -> (x1, y1).  For CCW
+    //      winding, a sample point is on the inside of this edge when
+    //      Evaluate() is >= 0, modulo the top-left tie-break below that
+    //      decides ownership of the shared E == 0 boundary.
+    //
+    //-------------------------------------------------------------------------
+    fn FromPoints(x0: LONG, y0: LONG, x1: LONG, y1: LONG) -> Self
+    {
+        return CEdgeFunction {
+            A: (y1 - y0) as LONGLONG,
+            B: (x0 - x1) as LONGLONG,
+            C: (x1 as LONGLONG)*(y0 as LONGLONG) - (x0 as LONGLONG)*(y1 as LONGLONG),
+        };
+    }
+
+    fn Evaluate(&self, x: LONG, y: LONG) -> LONGLONG
+    {
+        return self.A*(x as LONGLONG) + self.B*(y as LONGLONG) + self.C;
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CEdgeFunction::IsTopLeft
+    //
+    //  Synopsis:
+    //      Implements the top-left fill convention so adjacent triangles
+    //      sharing an edge neither double-cover nor crack the shared
+    //      pixels: an edge owns its E == 0 boundary when it is a top edge
+    //      (A == 0 && B < 0) or a left edge (A > 0).
+    //
+    //-------------------------------------------------------------------------
+    fn IsTopLeft(&self) -> bool
+    {
+        return self.A > 0 || (self.A == 0 && self.B < 0);
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   RasterizeTriPoint
+//
+//  Synopsis:
+//      Rasterize a single CCW triangle (in 28.4 fixed point) by walking
+//      its bounding box of subpixel samples and testing each against the
+//      three edge functions.  Because each E_i is affine, a step of one
+//      subpixel in X adds A_i and a step of one subpixel in Y adds B_i, so
+//      the per-sample test is three adds and three sign checks -- no
+//      division, no sorted active edge list.  A tile-parallel kernel would
+//      step a whole SIMD lane of samples per iteration; this scalar form
+//      is the reference a vectorized kernel must match bit-for-bit.
+//
+//-------------------------------------------------------------------------
+fn RasterizeTriPoint(
+    x0: LONG, y0: LONG,
+    x1: LONG, y1: LONG,
+    x2: LONG, y2: LONG,
+    pIGeometrySink: &dyn IGeometrySink
+    ) -> HRESULT
+{
+    let hr = S_OK;
+
+    let e0 = CEdgeFunction::FromPoints(x0, y0, x1, y1);
+    let e1 = CEdgeFunction::FromPoints(x1, y1, x2, y2);
+    let e2 = CEdgeFunction::FromPoints(x2, y2, x0, y0);
+
+    // Top-left tie-break bias: edges that don't own their E == 0 boundary
+    // are nudged so that shared edges are credited to exactly one triangle.
+    let bias0: LONGLONG = if e0.IsTopLeft() { 0 } else { -1 };
+    let bias1: LONGLONG = if e1.IsTopLeft() { 0 } else { -1 };
+    let bias2: LONGLONG = if e2.IsTopLeft() { 0 } else { -1 };
+
+    let nSubpixelXMin = min(x0, min(x1, x2));
+    let nSubpixelXMax = max(x0, max(x1, x2));
+    let nSubpixelYMin = min(y0, min(y1, y2));
+    let nSubpixelYMax = max(y0, max(y1, y2));
+
+    let nSampleStep = 1 << (c_nShift - 3); // one subpixel sample step
+
+    // Evaluate each E_i once at the bounding box's first sample, then walk
+    // it incrementally: + A_i*step per X step, + B_i*step per Y step.
+    let nStep = nSampleStep as LONGLONG;
+    let (nStepX0, nStepX1, nStepX2) = (e0.A*nStep, e1.A*nStep, e2.A*nStep);
+    let mut nRow0 = e0.Evaluate(nSubpixelXMin, nSubpixelYMin) + bias0;
+    let mut nRow1 = e1.Evaluate(nSubpixelXMin, nSubpixelYMin) + bias1;
+    let mut nRow2 = e2.Evaluate(nSubpixelXMin, nSubpixelYMin) + bias2;
+
+    let mut y = nSubpixelYMin;
+    while y <= nSubpixelYMax
+    {
+        let (mut w0, mut w1, mut w2) = (nRow0, nRow1, nRow2);
+        let mut x = nSubpixelXMin;
+        while x <= nSubpixelXMax
+        {
+            if (w0 >= 0 && w1 >= 0 && w2 >= 0)
+            {
+                // 28.4 fixed point -> integer pixel
+                IFC(pIGeometrySink.AddEdgeFunctionSample(
+                    x >> 4,
+                    y >> 4
+                    ));
+            }
+
+            w0 += nStepX0; w1 += nStepX1; w2 += nStepX2;
+            x += nSampleStep;
+        }
+
+        nRow0 += e0.B*nStep; nRow1 += e1.B*nStep; nRow2 += e2.B*nStep;
+        y += nSampleStep;
+    }
+
+Cleanup:
+    RRETURN(hr);
+}
+
+// GDI+/WPF path-point-type tag bits PathFigure point arrays are encoded
+// with; TessellatePathToTriangles only needs to recognize figure starts.
+const PathPointTypeStart: BYTE = 0;
+const PathPointTypePathTypeMask: BYTE = 0x07;
+
+//-------------------------------------------------------------------------
+//
+//  Function:   ToFix4
+//
+//  Synopsis:
+//      Convert a device-space coordinate to the 28.4 fixed point
+//      RasterizeTriPoint/CEdgeFunction operate in -- the same FIX4_ONE
+//      (== 16) scale RasterizePath already applies to m_rcClipBounds
+//      above.
+//
+//-------------------------------------------------------------------------
+fn ToFix4(r: f32) -> LONG
+{
+    return (r * (FIX4_ONE as f32)).round() as LONG;
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   TessellatePathToTriangles
+//
+//  Synopsis:
+//      Transform every point by pmatWorldTransform and fan-triangulate
+//      each figure (rgpt[iFigureStart], rgpt[i], rgpt[i + 1] for i in
+//      [iFigureStart + 1, iFigureEnd)) around its first vertex.  A figure
+//      boundary is any point flagged PathPointTypeStart, or the start of
+//      the array.
+//
+//      This intentionally does not flatten curves itself -- rgpt/rgTypes
+//      are expected to already be line-segment points, the same
+//      precondition FixedPointPathEnumerate's callers already satisfy for
+//      the trapezoidal path.  Fan triangulation is exact for the convex
+//      figures (rectangles, regular polygons) this backend is meant for;
+//      a concave figure degrades to visually-reasonable but not
+//      necessarily winding-correct coverage, which is acceptable since
+//      RasterizePath only routes here for MilFillMode::Alternate and
+//      falls back to the trapezoidal sweep otherwise.
+//
+//-------------------------------------------------------------------------
+fn TessellatePathToTriangles(
+    rgpt: &[MilPoint2F],
+    rgTypes: &[BYTE],
+    cPoints: UINT,
+    pmatWorldTransform: &CMILMatrix,
+    rgTriangles: &mut Vec<[MilPoint2F; 3]>
+    ) -> HRESULT
+{
+    let hr = S_OK;
+    let cPoints = cPoints as usize;
+
+    let mut rgTransformed: Vec<MilPoint2F> = Vec::with_capacity(cPoints);
+    for i in 0..cPoints
+    {
+        let (x, y) = pmatWorldTransform.Transform(rgpt[i].X, rgpt[i].Y);
+        rgTransformed.push(MilPoint2F { X: x, Y: y });
+    }
+
+    let mut iFigureStart = 0usize;
+    for i in 0..cPoints
+    {
+        let fStartsNewFigure = i > iFigureStart
+            && (rgTypes[i] & PathPointTypePathTypeMask) == PathPointTypeStart;
+
+        if fStartsNewFigure
+        {
+            iFigureStart = i;
+        }
+
+        let iNext = i + 1;
+        if iNext < cPoints
+            && (rgTypes[iNext] & PathPointTypePathTypeMask) != PathPointTypeStart
+            && i > iFigureStart
+        {
+            rgTriangles.push([
+                rgTransformed[iFigureStart],
+                rgTransformed[i],
+                rgTransformed[iNext],
+                ]);
+        }
+    }
+
+    RRETURN(hr);
+}
+
+impl CHwRasterizer {
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::RasterizeTrianglesEdgeFunction
+//
+//  Synopsis:
+//      Fan-triangulate the path's points with TessellatePathToTriangles
+//      (no curve flattening: rgpt/rgTypes are taken as line-segment
+//      points) and rasterize each triangle with RasterizeTriPoint
+//      instead of the scanline/DDA sweep.
+//
+//-------------------------------------------------------------------------
+fn RasterizeTrianglesEdgeFunction(&self,
+    rgpt: &[MilPoint2F],
+    rgTypes: &[BYTE],
+    cPoints: UINT,
+    pmatWorldTransform: &CMILMatrix
+    ) -> HERSULT
+{
+    let hr = S_OK;
+    let mut rgTriangles: Vec<[MilPoint2F; 3]> = Vec::new();
+
+    IFC(TessellatePathToTriangles(rgpt, rgTypes, cPoints, pmatWorldTransform, &mut rgTriangles));
+
+    for triangle in &rgTriangles
+    {
+        let x0 = ToFix4(triangle[0].X);
+        let y0 = ToFix4(triangle[0].Y);
+        let x1 = ToFix4(triangle[1].X);
+        let y1 = ToFix4(triangle[1].Y);
+        let x2 = ToFix4(triangle[2].X);
+        let y2 = ToFix4(triangle[2].Y);
+
+        IFC(RasterizeTriPoint(x0, y0, x1, y1, x2, y2, &*self.m_pIGeometrySink));
+    }
+
+Cleanup:
+    RRETURN(hr);
+}
+
+} // impl CHwRasterizer
+
+//-------------------------------------------------------------------------
+//
+//  Trait:      IGeometrySink
+//
+//  Synopsis:
+//      The output interface RasterizePath/RasterizeEdges feed -- one
+//      call per complex-scan coverage run or fast-path trapezoid.
+//      m_pIGeometrySink is the GPU/display-list-backed implementation in
+//      the full D3D pipeline; CA8CoverageSink and CXRenderTrapezoidSink
+//      below are CPU-only implementations that let this crate run
+//      without one.  AddComplexScanSampleMask/AddEdgeFunctionSample are
+//      only needed by sinks paired with CoverageOutputMode::PerSample or
+//      RasterizationMode::EdgeFunction respectively, so they default to
+//      E_NOTIMPL instead of forcing every sink to implement every mode.
+//
+//-------------------------------------------------------------------------
+trait IGeometrySink {
+    fn AddComplexScan(&mut self, nPixelY: INT, pIntervalSpanStart: &CCoverageInterval) -> HRESULT;
+
+    fn AddTrapezoid(&mut self,
+        rPixelYTop: f32, rPixelXTopLeft: f32, rPixelXTopRight: f32,
+        rPixelYBottom: f32, rPixelXBottomLeft: f32, rPixelXBottomRight: f32,
+        rPixelXLeftDelta: f32, rPixelXRightDelta: f32
+        ) -> HRESULT;
+
+    fn AddComplexScanSampleMask(&mut self, _nPixelY: INT, _pIntervalSpanStart: &CCoverageInterval) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
+
+    fn AddEdgeFunctionSample(&mut self, _nPixelX: INT, _nPixelY: INT, _rCoverage: f32) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
+
+    // CCellCoverageAccumulator/CEdgeFlagBitmaskAccumulator (see
+    // CoverageAccumulationMode) both resolve a scanline straight to a
+    // per-pixel coverage byte instead of the CCoverageInterval run-list
+    // AddComplexScan takes, so they feed the sink through this method
+    // instead -- rgCoverage[i] is the pixel at column (nXMin + i).
+    fn AddComplexScanCoverageBytes(&mut self, _nPixelY: INT, _nXMin: INT, _rgCoverage: &[BYTE]) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Struct:     CA8CoverageSink
+//
+//  Synopsis:
+//      A CPU-only output target, modeled on the OpenVG reference
+//      rasterizer's coverage accumulation, that writes an 8-bit
+//      per-pixel alpha mask directly into a caller-supplied buffer
+//      (pointer, width, height, stride) instead of feeding an
+//      IGeometrySink/GPU pipeline.  This lets the crate be used as a
+//      standalone software path filler -- for glyph atlases, clip masks,
+//      or headless rendering -- without a D3D device.
+//
+//      The complex-scan path emits runs of (x_start, x_end, coverage)
+//      directly into the A8 row; simple trapezoids are filled
+//      analytically.  All the existing edge/DDA/fill-mode logic is
+//      reused unchanged -- this struct only changes where coverage ends
+//      up.
+//
+//-------------------------------------------------------------------------
+struct CA8CoverageSink {
+    pbBuffer: *mut BYTE,  // Caller-owned A8 buffer
+    nWidth: INT,
+    nHeight: INT,
+    nStride: INT,
+}
+
+impl CA8CoverageSink {
+    fn new(pbBuffer: *mut BYTE, nWidth: INT, nHeight: INT, nStride: INT) -> Self
+    {
+        return CA8CoverageSink { pbBuffer, nWidth, nHeight, nStride };
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CA8CoverageSink::AddComplexScan
+    //
+    //  Synopsis:
+    //      Write each (x_start, x_end, coverage) run from a complex scan's
+    //      coverage interval list directly into row nPixelY of the A8
+    //      buffer, clipped to [0, nWidth) x [0, nHeight).
+    //
+    //-------------------------------------------------------------------------
+    fn AddComplexScan(&mut self, nPixelY: INT, pIntervalSpanStart: &CCoverageInterval) -> HRESULT
+    {
+        let hr = S_OK;
+
+        if (nPixelY < 0 || nPixelY >= self.nHeight)
+        {
+            return hr;
+        }
+
+        let pRow = unsafe { self.pbBuffer.offset((nPixelY * self.nStride) as isize) };
+
+        let mut pInterval = pIntervalSpanStart;
+        while (pInterval.X_start != INT_MAX)
+        {
+            let nXStart = max(0, pInterval.X_start);
+            let nXEnd   = min(self.nWidth, pInterval.X_end);
+
+            for x in nXStart..nXEnd
+            {
+                unsafe { *pRow.offset(x as isize) = pInterval.Coverage; }
+            }
+
+            pInterval = pInterval.Next;
+        }
+
+        return hr;
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CA8CoverageSink::AddTrapezoid
+    //
+    //  Synopsis:
+    //      Fill a trapezoid analytically, row by row, computing the left
+    //      and right X bounds (plus their falloff deltas) at each pixel
+    //      row by linear interpolation between top and bottom, and writing
+    //      coverage directly instead of handing the shape off to a
+    //      geometry sink for tessellation.
+    //
+    //-------------------------------------------------------------------------
+    fn AddTrapezoid(&mut self,
+        rPixelYTop: f32, rPixelXTopLeft: f32, rPixelXTopRight: f32,
+        rPixelYBottom: f32, rPixelXBottomLeft: f32, rPixelXBottomRight: f32,
+        rPixelXLeftDelta: f32, rPixelXRightDelta: f32
+        ) -> HRESULT
+    {
+        let hr = S_OK;
+
+        let nRowTop    = max(0, rPixelYTop.floor() as INT);
+        let nRowBottom = min(self.nHeight, rPixelYBottom.ceil() as INT);
+        let rHeight    = rPixelYBottom - rPixelYTop;
+
+        for nRow in nRowTop..nRowBottom
+        {
+            let rT = ((nRow as f32) + 0.5 - rPixelYTop) / rHeight;
+            let rXLeft  = rPixelXTopLeft  + rT * (rPixelXBottomLeft  - rPixelXTopLeft)  - rPixelXLeftDelta;
+            let rXRight = rPixelXTopRight + rT * (rPixelXBottomRight - rPixelXTopRight) + rPixelXRightDelta;
+
+            let nXStart = max(0, rXLeft.floor() as INT);
+            let nXEnd   = min(self.nWidth, rXRight.ceil() as INT);
+
+            let pRow = unsafe { self.pbBuffer.offset((nRow * self.nStride) as isize) };
+            for x in nXStart..nXEnd
+            {
+                // A full analytic fractional-coverage computation would
+                // weight each pixel by its overlap with [rXLeft, rXRight);
+                // we approximate with full coverage across the span and
+                // linear falloff at the two edge pixels, matching the
+                // same 12.5%-error linear approximation OutputTrapezoids
+                // already accepts for its falloff region.
+                let rCoverage = if (x as f32) < rXLeft + 1.0 {
+                    (((x as f32) + 1.0 - rXLeft).min(1.0).max(0.0)) * 255.0
+                } else if (x as f32) > rXRight - 1.0 {
+                    ((rXRight - (x as f32)).min(1.0).max(0.0)) * 255.0
+                } else {
+                    255.0
+                };
+
+                unsafe { *pRow.offset(x as isize) = rCoverage as BYTE; }
+            }
+        }
+
+        return hr;
+    }
+}
+
+impl IGeometrySink for CA8CoverageSink {
+    fn AddComplexScan(&mut self, nPixelY: INT, pIntervalSpanStart: &CCoverageInterval) -> HRESULT
+    {
+        return CA8CoverageSink::AddComplexScan(self, nPixelY, pIntervalSpanStart);
+    }
+
+    fn AddTrapezoid(&mut self,
+        rPixelYTop: f32, rPixelXTopLeft: f32, rPixelXTopRight: f32,
+        rPixelYBottom: f32, rPixelXBottomLeft: f32, rPixelXBottomRight: f32,
+        rPixelXLeftDelta: f32, rPixelXRightDelta: f32
+        ) -> HRESULT
+    {
+        return CA8CoverageSink::AddTrapezoid(
+            self, rPixelYTop, rPixelXTopLeft, rPixelXTopRight,
+            rPixelYBottom, rPixelXBottomLeft, rPixelXBottomRight,
+            rPixelXLeftDelta, rPixelXRightDelta
+            );
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CA8CoverageSink::AddComplexScanCoverageBytes
+    //
+    //  Synopsis:
+    //      Write an already-resolved per-pixel coverage run (as produced by
+    //      CCellCoverageAccumulator/CEdgeFlagBitmaskAccumulator::Resolve)
+    //      directly into row nPixelY of the A8 buffer, clipped to
+    //      [0, nWidth) x [0, nHeight) the same way AddComplexScan is.
+    //
+    //-------------------------------------------------------------------------
+    fn AddComplexScanCoverageBytes(&mut self, nPixelY: INT, nXMin: INT, rgCoverage: &[BYTE]) -> HRESULT
+    {
+        let hr = S_OK;
+
+        if (nPixelY < 0 || nPixelY >= self.nHeight)
+        {
+            return hr;
+        }
+
+        let pRow = unsafe { self.pbBuffer.offset((nPixelY * self.nStride) as isize) };
+
+        for (i, &coverage) in rgCoverage.iter().enumerate()
+        {
+            let x = nXMin + (i as INT);
+            if (x >= 0 && x < self.nWidth)
+            {
+                unsafe { *pRow.offset(x as isize) = coverage; }
+            }
+        }
+
+        return hr;
+    }
+}
+
+impl CHwRasterizer {
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::RasterizeToA8Buffer
+//
+//  Synopsis:
+//      Rasterize the path directly into a caller-supplied A8 coverage
+//      buffer instead of an IGeometrySink/GPU pipeline, by substituting a
+//      CA8CoverageSink for m_pIGeometrySink for the duration of the call.
+//      All edge/DDA/fill-mode logic in RasterizePath is reused unchanged.
+//
+//-------------------------------------------------------------------------
+fn RasterizeToA8Buffer(&mut self,
+    pbBuffer: *mut BYTE,
+    nWidth: INT,
+    nHeight: INT,
+    nStride: INT
+    ) -> HERSULT
+{
+    let hr = S_OK;
+    let mut sink = CA8CoverageSink::new(pbBuffer, nWidth, nHeight, nStride);
+
+    IFC(self.SendGeometry(Rc::new(sink)));
 
+Cleanup:
+    RRETURN(hr);
+}
+
+} // impl CHwRasterizer
+
+//-------------------------------------------------------------------------
+//
+//  Enum:       CoverageAccumulationMode
+//
+//  Synopsis:
+//      Selects how the complex-scan path (the fallback RasterizeEdges
+//      takes when ComputeTrapezoidsEndScan can't produce a trapezoid)
+//      turns edge crossings into per-pixel coverage.  Supersampled8x8 is
+//      the original CCoverageBuffer/m_coverageBuffer accumulation at the
+//      fixed 8x8 subpixel grid (c_nShift); CellCoverage is the analytic
+//      cairo/FreeType-style single-pass accumulator below; EdgeFlagBitmask
+//      is the edge-flag subsample-bitmask accumulator further below,
+//      trading CellCoverage's exact fractional area for a cheap S-bit
+//      per-pixel mask that's exact at the subsample grid's own
+//      resolution -- useful when S matches the target's own MSAA rate.
+//
+//-------------------------------------------------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum CoverageAccumulationMode
+{
+    Supersampled8x8,
+    CellCoverage,
+    EdgeFlagBitmask,
+}
+
+//-------------------------------------------------------------------------
+//
+//  Enum:       CoverageOutputMode
+//
+//  Synopsis:
+//      Borrowing SWR's pixel-rate/sample-rate distinction: Resolved is
+//      today's behavior, where GenerateOutputAndClearCoverage blends every
+//      subpixel scanline in a pixel row down into one [0, 255] coverage
+//      byte before handing it to the sink.  PerSample instead keeps each
+//      of the m_samplePattern.SampleCount() subpixel samples separate and
+//      emits a raw per-pixel sample mask, letting a GPU consumer do its
+//      own MSAA resolve or feed the bits straight into a depth/stencil
+//      sample test.  Resolved coverage is always recoverable from a
+//      PerSample mask by popcount/average (see ComputeCoverageFromSamplePattern),
+//      so switching modes never changes what a pixel "means", only how
+//      many bits of it the sink gets to see.
+//
+//-------------------------------------------------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum CoverageOutputMode
+{
+    Resolved,
+    PerSample,
+}
+
+//-------------------------------------------------------------------------
+//
+//  Struct:     CCellCoverageAccumulator
+//
+//  Synopsis:
+//      Analytic per-scanline coverage accumulator modeled on the
+//      cairo/FreeType "cell" rasterizer.  As each edge is walked down a
+//      scanline, its contribution is distributed into two per-pixel-column
+//      accumulators:
+//
+//        cover[x] -- the signed fractional height the edge spans within
+//                    column x; once summed left-to-right this tells us how
+//                    much of everything to the right of x is "inside".
+//        area[x]  -- twice the signed trapezoidal area the edge sweeps
+//                    within column x itself.
+//
+//      After all edges for a scanline are processed, a single left-to-
+//      right sweep maintaining a running `cover` total resolves coverage
+//      at column x as (running_cover - area[x]/2), giving exact 256-level
+//      antialiasing in one pass instead of the eight passes the 8x8
+//      supersampled buffer needs.
+//
+//-------------------------------------------------------------------------
+struct CCellCoverageAccumulator {
+    rgCover: Vec<f32>,  // one entry per pixel column in the scanline's bounding range
+    rgArea:  Vec<f32>,
+    nXMin: INT,         // pixel column rgCover[0]/rgArea[0] corresponds to
+}
+
+impl CCellCoverageAccumulator {
+    fn new(nXMin: INT, nXMax: INT) -> Self
+    {
+        let nCount = (nXMax - nXMin + 1) as usize;
+        return CCellCoverageAccumulator {
+            rgCover: vec![0.0; nCount],
+            rgArea:  vec![0.0; nCount],
+            nXMin,
+        };
+    }
+
+    fn Reset(&mut self)
+    {
+        for v in self.rgCover.iter_mut() { *v = 0.0; }
+        for v in self.rgArea.iter_mut()  { *v = 0.0; }
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CCellCoverageAccumulator::AddEdgeSpan
+    //
+    //  Synopsis:
+    //      Distribute one edge's contribution to a single scanline into
+    //      rgCover/rgArea.  rYTop/rYBottom (within [0, 1), the fractional
+    //      height the edge occupies on this scanline) and rXTop/rXBottom
+    //      (the edge's X position, in pixel space, at those two heights)
+    //      describe the edge segment clipped to this scanline.
+    //
+    //      Handles: edges that enter and exit the same pixel column
+    //      (rXTop and rXBottom resolve to the same column -- a single
+    //      trapezoid contribution to that column), perfectly vertical
+    //      edges (rXTop == rXBottom, dx == 0, contribution is a rectangle
+    //      rather than a trapezoid), and edges that are pre-clipped to the
+    //      scanline's Y bounds by the caller so rYTop/rYBottom already
+    //      stay within [0, 1) -- this function does not re-clip Y.
+    //
+    //-------------------------------------------------------------------------
+    fn AddEdgeSpan(&mut self, rYTop: f32, rYBottom: f32, rXTop: f32, rXBottom: f32, nWindingDirection: INT)
+    {
+        let rDeltaY = (rYBottom - rYTop) * (nWindingDirection as f32);
+        if (rDeltaY == 0.0)
+        {
+            return;
+        }
+
+        let nColTop    = (rXTop.floor()    as INT) - self.nXMin;
+        let nColBottom = (rXBottom.floor() as INT) - self.nXMin;
+
+        if (nColTop == nColBottom)
+        {
+            // Edge stays within a single column: cover is the full dy,
+            // area is the dy-weighted trapezoid formed by the fractional
+            // X offsets at entry/exit within the column.
+            let rXFracTop    = rXTop    - (rXTop.floor());
+            let rXFracBottom = rXBottom - (rXBottom.floor());
+
+            self.rgCover[nColTop as usize] += rDeltaY;
+            self.rgArea[nColTop as usize]  += rDeltaY * (rXFracTop + rXFracBottom);
+            return;
+        }
+
+        // Edge crosses one or more column boundaries: walk column by
+        // column, splitting dy proportionally to the X distance covered
+        // in each column (this is where a sloped edge differs from the
+        // zero-dx vertical case above, which always lands in the
+        // single-column branch).
+        let rDx = rXBottom - rXTop;
+        let nColStart = min(nColTop, nColBottom);
+        let nColEnd   = max(nColTop, nColBottom);
+
+        let mut rXPrev = rXTop;
+        let mut rYPrev = rYTop;
+
+        for nCol in nColStart..=nColEnd
+        {
+            let rColRightEdge = ((self.nXMin + nCol + 1) as f32).min(rXTop.max(rXBottom));
+            let rT = if rDx != 0.0 { ((rColRightEdge - rXTop) / rDx).min(1.0).max(0.0) } else { 1.0 };
+            let rYAtColRight = rYTop + rT * (rYBottom - rYTop);
+
+            let rDeltaYCol = (rYAtColRight - rYPrev) * (nWindingDirection as f32);
+            let rXFracPrev = rXPrev - (rXPrev.floor());
+            let rXFracHere = rColRightEdge - (rColRightEdge.floor()).min(rColRightEdge);
+
+            self.rgCover[nCol as usize] += rDeltaYCol;
+            self.rgArea[nCol as usize]  += rDeltaYCol * (rXFracPrev + rXFracHere);
+
+            rXPrev = rColRightEdge;
+            rYPrev = rYAtColRight;
+        }
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CCellCoverageAccumulator::Resolve
+    //
+    //  Synopsis:
+    //      Sweep left to right maintaining a running cover total; pixel x's
+    //      coverage is (running_cover - area[x]/2), clamped to [0, 1] and
+    //      scaled to [0, 255].  GenerateOutputAndClearCoverage hands the
+    //      result to the sink's AddComplexScanCoverageBytes, not to the
+    //      AddComplexScan run list the 8x8 supersampled path uses.
+    //
+    //-------------------------------------------------------------------------
+    fn Resolve(&self, rgCoverageOut: &mut [BYTE])
+    {
+        let mut rRunningCover = 0.0f32;
+        for (i, coverage) in rgCoverageOut.iter_mut().enumerate()
+        {
+            rRunningCover += self.rgCover[i];
+            let rCoverage = (rRunningCover - self.rgArea[i] * 0.5).max(0.0).min(1.0);
+            *coverage = (rCoverage * 255.0) as BYTE;
+        }
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Struct:     CEdgeFlagBitmaskAccumulator
+//
+//  Synopsis:
+//      Edge-flag subsample-bitmask coverage accumulator: for each of the
+//      S = m_samplePattern.SampleCount() subpixel scanlines making up a
+//      pixel row, walk the active edge list once and OR a single "this
+//      subsample row is inside the path here" bit into every pixel column
+//      the row spans, rather than accumulating analytic area the way
+//      CCellCoverageAccumulator does.  The per-pixel result is an S-bit
+//      mask (rgMask[x] bit i set <=> subsample row i is inside at column
+//      x); Resolve turns each mask into a coverage byte by popcount,
+//      exactly like ComputeCoverageFromSamplePattern already does for the
+//      trapezoid path's sample-pattern test, so both backends agree on
+//      what "coverage" means for a given sample count.  Cheap (S bits/
+//      pixel instead of two floats) and gives exact coverage at very thin
+//      or overlapping sub-pixel features where CCellCoverageAccumulator's
+//      area bookkeeping and the trapezoid fast path both have to fall
+//      back to the complex scan anyway.
+//
+//-------------------------------------------------------------------------
+struct CEdgeFlagBitmaskAccumulator {
+    rgMask: Vec<UINT16>,  // one S-bit mask per pixel column in the row's bounding range
+    nXMin: INT,
+    nSampleCount: UINT,   // S; also the popcount LUT's bit width when <= 8
+    rgPopcountLut: Vec<BYTE>,  // precomputed popcount(i) for i in [0, 256), used when S <= 8
+}
+
+impl CEdgeFlagBitmaskAccumulator {
+    fn new(nXMin: INT, nXMax: INT, nSampleCount: UINT) -> Self
+    {
+        let nCount = (nXMax - nXMin + 1) as usize;
+
+        // A lookup table only pays for itself at small S: 256 bytes
+        // covers every mask value once S <= 8, versus walking count_ones()
+        // directly (cheap regardless, but the request calls for a table
+        // when it's worth building).
+        let rgPopcountLut = if nSampleCount <= 8 {
+            (0u32..256).map(|i| i.count_ones() as BYTE).collect()
+        } else {
+            Vec::new()
+        };
+
+        return CEdgeFlagBitmaskAccumulator {
+            rgMask: vec![0; nCount],
+            nXMin,
+            nSampleCount,
+            rgPopcountLut,
+        };
+    }
+
+    fn Reset(&mut self)
+    {
+        for v in self.rgMask.iter_mut() { *v = 0; }
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CEdgeFlagBitmaskAccumulator::AddSubsampleRow
+    //
+    //  Synopsis:
+    //      Process one subsample row's edge crossings (nRgXCrossing,
+    //      already sorted left to right) and OR nRowBit into rgMask for
+    //      every pixel column the row covers.  fWinding selects how
+    //      crossings turn "inside" on and off: Alternate toggles at every
+    //      crossing (even-odd), matching FillEdgesAlternating; Winding
+    //      accumulates rgWindingDelta per crossing and treats the run
+    //      "inside" wherever the running total is nonzero, matching
+    //      FillEdgesWinding.  This mirrors those two functions' semantics
+    //      exactly, just applied to one subsample row's bit instead of a
+    //      fractional coverage interval.
+    //
+    //-------------------------------------------------------------------------
+    fn AddSubsampleRow(&mut self, rgXCrossing: &[f32], rgWindingDelta: &[INT], fWinding: bool, nRowBit: UINT)
+    {
+        let nRowMask: UINT16 = 1 << nRowBit;
+
+        let mut nWindingCount: INT = 0;
+        let mut iCrossing = 0;
+        while iCrossing < rgXCrossing.len()
+        {
+            if fWinding
+            {
+                nWindingCount += rgWindingDelta[iCrossing];
+            }
+
+            let fIsInside = if fWinding { nWindingCount != 0 } else { (iCrossing & 1) == 0 };
+
+            if fIsInside
+            {
+                // Inside from this crossing to the next one: OR this row's
+                // bit across that pixel range.  The ranges of one span
+                // abut, so a winding count that stays nonzero across
+                // several crossings marks the whole span.
+                let nXStart = max(self.nXMin, rgXCrossing[iCrossing].floor() as INT);
+                let nXEndExclusive = if iCrossing + 1 < rgXCrossing.len() {
+                    rgXCrossing[iCrossing + 1].ceil() as INT
+                } else {
+                    nXStart
+                };
+
+                for nX in nXStart..nXEndExclusive
+                {
+                    let iCol = (nX - self.nXMin) as usize;
+                    if iCol < self.rgMask.len()
+                    {
+                        self.rgMask[iCol] |= nRowMask;
+                    }
+                }
+            }
+
+            iCrossing += 1;
+        }
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CEdgeFlagBitmaskAccumulator::Resolve
+    //
+    //  Synopsis:
+    //      (popcount(mask) * 255) / S for every column, via
+    //      rgPopcountLut when S <= 8 (mask fits in a byte) and
+    //      UINT16::count_ones() otherwise.
+    //
+    //-------------------------------------------------------------------------
+    fn Resolve(&self, rgCoverageOut: &mut [BYTE])
+    {
+        for (i, coverage) in rgCoverageOut.iter_mut().enumerate()
+        {
+            let nMask = self.rgMask[i];
+            let nSet = if self.nSampleCount <= 8 {
+                self.rgPopcountLut[nMask as usize] as UINT
+            } else {
+                nMask.count_ones()
+            };
+
+            *coverage = ((nSet * 255) / self.nSampleCount) as BYTE;
+        }
+    }
+}
+
+impl CHwRasterizer {
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetCoverageAccumulationMode
+//
+//  Synopsis:
+//      Choose among the original 8x8 supersampled complex-scan buffer, the
+//      analytic CCellCoverageAccumulator, and the edge-flag
+//      CEdgeFlagBitmaskAccumulator above.  Supersampled8x8 output reaches
+//      the sink through AddComplexScan; CellCoverage and EdgeFlagBitmask
+//      output reaches it through AddComplexScanCoverageBytes (see
+//      GenerateOutputAndClearCoverage), which returns E_NOTIMPL on sinks
+//      that don't override it.
+//
+//-------------------------------------------------------------------------
+fn SetCoverageAccumulationMode(&mut self, mode: CoverageAccumulationMode)
+{
+    self.m_coverageAccumulationMode = mode;
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetCoverageOutputMode
+//
+//  Synopsis:
+//      Choose between Resolved (the default single coverage byte per
+//      pixel) and PerSample output (see CoverageOutputMode).  Only
+//      affects GenerateOutputAndClearCoverage, i.e. the complex-scan
+//      fallback path; ComputeTrapezoidsEndScan's fast trapezoids are
+//      already fully covered by construction wherever the sweep can use
+//      them, so there's no separate sample mask to preserve there.
+//
+//-------------------------------------------------------------------------
+fn SetCoverageOutputMode(&mut self, mode: CoverageOutputMode)
+{
+    self.m_coverageOutputMode = mode;
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetGammaCorrection
+//
+//  Synopsis:
+//      Enable or disable the gamma-correction stage in
+//      GenerateOutputAndClearCoverage, and set the gamma used to build
+//      m_gammaTable.  rGamma == 1.0 is linear (identical to the crate's
+//      historical output); use SetSRGBGammaCorrection for the common
+//      sRGB-target case.  Rebuilds the table immediately so the next
+//      scanline sees it, rather than lazily on first use.
+//
+//-------------------------------------------------------------------------
+fn SetGammaCorrection(&mut self, fEnable: bool, rGamma: f32)
+{
+    self.m_fGammaCorrection = fEnable;
+    self.m_gammaTable.Build(rGamma);
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetSRGBGammaCorrection
+//
+//  Synopsis:
+//      Convenience wrapper around SetGammaCorrection using c_rSRGBGamma,
+//      for the common case of an sRGB-encoded render target.
+//
+//-------------------------------------------------------------------------
+fn SetSRGBGammaCorrection(&mut self, fEnable: bool)
+{
+    self.SetGammaCorrection(fEnable, c_rSRGBGamma);
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::GetVerticalSampleCount
+//
+//  Synopsis:
+//      The number of subpixel samples (1 << m_samplePattern.Shift(),
+//      equivalently m_samplePattern.SampleCount()) a PerSample coverage
+//      mask carries per pixel.  Callers need this to know how many bits
+//      of the mask GenerateOutputAndClearCoverage actually populates
+//      before they attempt their own resolve.
+//
+//-------------------------------------------------------------------------
+fn GetVerticalSampleCount(&self) -> UINT
+{
+    return self.m_samplePattern.SampleCount();
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::SetPreventDropout
+//
+//  Synopsis:
+//      Enable dropout prevention for hairline contours and glyph stems
+//      narrower than one pixel.  Normally, if a scanline's active-edge
+//      pair collapses to zero width within a pixel cell, FillEdgesAlternating/
+//      FillEdgesWinding emit no coverage for that cell and degenerate
+//      geometry like a zero-area line can end up producing no trapezoids
+//      at all, surfacing as WGXHR_EMPTYFILL.  With this enabled,
+//      RasterizeEdges additionally asks the coverage buffer to check,
+//      column by column, whether an edge transition passed through a
+//      pixel even though the resolved span was empty, and if so emits a
+//      minimum-width coverage interval proportional to the fractional
+//      crossing so the contour still produces visible ink.  Off by
+//      default so existing callers see unchanged, exact output.
+//
+//-------------------------------------------------------------------------
+fn SetPreventDropout(&mut self, fEnable: bool)
+{
+    self.m_fPreventDropout = fEnable;
+}
+
+} // impl CHwRasterizer
+
+//-------------------------------------------------------------------------
+//
+// SIMD batch DDA stepping
+//
+//  Synopsis:
+//      AdvanceDDAMultipleSteps and the per-subpixel-row coverage
+//      accumulation in CCoverageBuffer are scalar and run once per edge
+//      per step; on paths with many active edges this dominates.
+//      Following the SIMD rasterizer design in the Mesa SWR core, the
+//      functions below advance up to c_nSimdLaneCount edges' DDA state
+//      (X, Error, Dx, ErrorUp, ErrorDown) at once using packed integer
+//      lanes.  The kernel is selected at runtime based on active-edge
+//      count and target feature detection; AdvanceDDAOneEdgeScalar
+//      (AdvanceDDAMultipleSteps's arithmetic, one edge at a time) is
+//      always the fallback, and output must be bit-identical between the
+//      two so existing callers can't tell which one ran.
+//
+//-------------------------------------------------------------------------
+
+const c_nSimdLaneCount: usize = 8;  // AVX2: 8 lanes of i32
+
+//-------------------------------------------------------------------------
+//
+//  Function:   AdvanceDDAMultipleStepsSimd8
+//
+//  Synopsis:
+//      Vectorized counterpart to AdvanceDDAMultipleSteps that advances up
+//      to 8 independent edges (not a left/right pair -- 8 distinct edges)
+//      by the same nSubpixelYAdvance in one pass.  Only the X advance
+//      (X + nSubpixelYAdvance*Dx) is done with packed 32-bit arithmetic,
+//      matching AdvanceDDAMultipleSteps's own comment that this product
+//      can't overflow 32 bits.  The error advance (Error +
+//      nSubpixelYAdvance*ErrorUp) is exactly the product
+//      AdvanceDDAMultipleSteps widens to 64 bits via Int32x32To64 to
+//      avoid overflow, so it's computed the same way here, per lane,
+//      rather than with a 32-bit packed multiply that could silently
+//      wrap where the scalar path wouldn't -- that mismatch would have
+//      broken the bit-identical guarantee this function exists for.
+//
+//-------------------------------------------------------------------------
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn AdvanceDDAMultipleStepsSimd8(
+    rgEdges: &[NonNull<CEdge>; c_nSimdLaneCount],
+    nSubpixelYAdvance: INT,
+    rgSubpixelXBottom: &mut [INT; c_nSimdLaneCount],
+    rgSubpixelErrorBottom: &mut [INT; c_nSimdLaneCount]
+    )
+{
+    use std::arch::x86_64::*;
+
+    let mut rgX: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+    let mut rgDx: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+    let mut rgError: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+    let mut rgErrorUp: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+    let mut rgErrorDown: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+
+    for i in 0..c_nSimdLaneCount
+    {
+        let edge = rgEdges[i].as_ref();
+        rgX[i]         = edge.X;
+        rgDx[i]        = edge.Dx;
+        rgError[i]     = edge.Error;
+        rgErrorUp[i]   = edge.ErrorUp;
+        rgErrorDown[i] = edge.ErrorDown;
+    }
+
+    let vAdvance = _mm256_set1_epi32(nSubpixelYAdvance);
+    let vX       = _mm256_loadu_si256(rgX.as_ptr() as *const __m256i);
+    let vDx      = _mm256_loadu_si256(rgDx.as_ptr() as *const __m256i);
+
+    // nSubpixelXBottom = X + nSubpixelYAdvance*Dx  (lane-wise)
+    let vXBottom = _mm256_add_epi32(vX, _mm256_mullo_epi32(vAdvance, vDx));
+
+    let mut rgXBottom: [i32; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+    _mm256_storeu_si256(rgXBottom.as_mut_ptr() as *mut __m256i, vXBottom);
+
+    // The error advance and the "is error >= 0, and by how much do we
+    // need to correct X and subtract from error" fixup both involve a
+    // per-lane division by that lane's own ErrorDown, which AVX2 doesn't
+    // do natively for integers; we do both scalar, lane by lane, using
+    // the same 64-bit-widened arithmetic as AdvanceDDAMultipleSteps so
+    // results match exactly.
+    for i in 0..c_nSimdLaneCount
+    {
+        let mut nXBottom = rgXBottom[i];
+        let llErrorBottom = (rgError[i] as LONGLONG) + Int32x32To64(nSubpixelYAdvance, rgErrorUp[i]);
+        let mut nErrorBottom;
+
+        if (llErrorBottom >= 0)
+        {
+            let llXDelta = llErrorBottom / (rgErrorDown[i] as LONGLONG) + 1;
+            let nXDelta = llXDelta as INT;
+            nXBottom += nXDelta;
+            nErrorBottom = (llErrorBottom - Int32x32To64(rgErrorDown[i], nXDelta)) as INT;
+        }
+        else
+        {
+            nErrorBottom = llErrorBottom as INT;
+        }
+
+        rgSubpixelXBottom[i] = nXBottom;
+        rgSubpixelErrorBottom[i] = nErrorBottom;
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   SelectAndAdvanceDDABatch
+//
+//  Synopsis:
+//      Runtime dispatch point: advance cEdges edges by nSubpixelYAdvance,
+//      using the AVX2 kernel above when the target supports it and the
+//      batch is large enough to amortize the setup cost, falling back to
+//      AdvanceDDAOneEdgeScalar otherwise.  Both kernels must produce
+//      bit-identical (X, Error) results for the same input, so callers
+//      never need to know which one ran.
+//
+//      Not yet called from RasterizeEdges/ComputeTrapezoidsEndScan: those
+//      walk the active-edge list (and call AdvanceDDAMultipleSteps)
+//      pairwise, left/right edge at a time, against per-trapezoid state
+//      that's still the file's untranslated original C++.  Batching
+//      across the full active-edge list for a scanline would mean
+//      restructuring that loop to collect edges into a flat batch first,
+//      which is out of scope here; this is the batch-advance primitive
+//      that restructuring would call.
+//
+//      error_advance_needs_64_bits below constructs a real CEdge and
+//      asserts AdvanceDDAMultipleStepsSimd8 and AdvanceDDAOneEdgeScalar
+//      agree bit-for-bit on an input that overflows a 32-bit multiply,
+//      so a regression that drops either kernel's 64-bit widening is
+//      caught directly rather than inferred from arithmetic in isolation.
+//
+//-------------------------------------------------------------------------
+fn SelectAndAdvanceDDABatch(
+    rgEdges: &[NonNull<CEdge>],
+    nSubpixelYAdvance: INT,
+    rgSubpixelXBottom: &mut [INT],
+    rgSubpixelErrorBottom: &mut [INT]
+    )
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        if (rgEdges.len() >= c_nSimdLaneCount && is_x86_feature_detected!("avx2"))
+        {
+            let mut nBase = 0;
+            while (nBase + c_nSimdLaneCount <= rgEdges.len())
+            {
+                let mut rgLane: [NonNull<CEdge>; c_nSimdLaneCount] = [rgEdges[nBase]; c_nSimdLaneCount];
+                for i in 0..c_nSimdLaneCount
+                {
+                    rgLane[i] = rgEdges[nBase + i];
+                }
+
+                let mut rgXOut: [INT; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+                let mut rgErrOut: [INT; c_nSimdLaneCount] = [0; c_nSimdLaneCount];
+
+                unsafe {
+                    AdvanceDDAMultipleStepsSimd8(&rgLane, nSubpixelYAdvance, &mut rgXOut, &mut rgErrOut);
+                }
+
+                for i in 0..c_nSimdLaneCount
+                {
+                    rgSubpixelXBottom[nBase + i] = rgXOut[i];
+                    rgSubpixelErrorBottom[nBase + i] = rgErrOut[i];
+                }
+
+                nBase += c_nSimdLaneCount;
+            }
+
+            // Tail edges that don't fill a full SIMD lane fall through to
+            // the scalar path below.
+            for i in nBase..rgEdges.len()
+            {
+                unsafe {
+                    AdvanceDDAOneEdgeScalar(rgEdges[i], nSubpixelYAdvance, &mut rgSubpixelXBottom[i], &mut rgSubpixelErrorBottom[i]);
+                }
+            }
+
+            return;
+        }
+    }
+
+    for i in 0..rgEdges.len()
+    {
+        unsafe {
+            AdvanceDDAOneEdgeScalar(rgEdges[i], nSubpixelYAdvance, &mut rgSubpixelXBottom[i], &mut rgSubpixelErrorBottom[i]);
+        }
+    }
+}
+
+//-------------------------------------------------------------------------
+//
+//  Function:   AdvanceDDAOneEdgeScalar
+//
+//  Synopsis:
+//      Single-edge scalar fallback with the same overflow-safe 64-bit
+//      error arithmetic as AdvanceDDAMultipleSteps, used both as the tail
+//      handler after a SIMD batch and as the whole-batch fallback when no
+//      vector kernel is available for the target.  Unsafe because it
+//      dereferences the raw NonNull<CEdge> the active-edge list is built
+//      from.
+//
+//-------------------------------------------------------------------------
+unsafe fn AdvanceDDAOneEdgeScalar(
+    pEdge: NonNull<CEdge>,
+    nSubpixelYAdvance: INT,
+    nSubpixelXBottom: &mut INT,
+    nSubpixelErrorBottom: &mut INT
+    )
+{
+    let edge = pEdge.as_ref();
+    let mut nXBottom = edge.X + nSubpixelYAdvance*edge.Dx;
+    let llErrorBottom = (edge.Error as LONGLONG) + Int32x32To64(nSubpixelYAdvance, edge.ErrorUp);
+
+    if (llErrorBottom >= 0)
+    {
+        let nXDelta = ((llErrorBottom / (edge.ErrorDown as LONGLONG)) as INT) + 1;
+        nXBottom += nXDelta;
+        *nSubpixelErrorBottom = (llErrorBottom - Int32x32To64(edge.ErrorDown, nXDelta)) as INT;
+    }
+    else
+    {
+        *nSubpixelErrorBottom = llErrorBottom as INT;
+    }
+
+    *nSubpixelXBottom = nXBottom;
+}
+
+//-------------------------------------------------------------------------
+//
+// pixman/RENDER xTrapezoid output sink
+//
+//  Synopsis:
+//      OutputTrapezoids feeds AddTrapezoid with WPF's 8-float trapezoid
+//      representation (top/bottom y, four x's, two expand radii).  The
+//      sink below instead serializes each trapezoid into the X RENDER
+//      xTrapezoid representation: two full edges, each a line given by
+//      its own top/bottom y and a pair of fixed-point (x, y) points, plus
+//      the trapezoid's overall top/bottom scanline bounds.  This lets the
+//      tessellation this crate produces be handed directly to
+//      RENDER/pixman-style consumers or test harnesses.
+//
+//      WPF's trapezoids overlap by the 1+1/m expand region on each side
+//      (see OutputTrapezoids); xTrapezoid expects non-overlapping left and
+//      right bounding edges, so this sink un-expands by rPixelXLeftDelta/
+//      rPixelXRightDelta before emitting the edges.
+//
+//-------------------------------------------------------------------------
+
+// 24.8 fixed point, matching the X RENDER xFixed convention.
+type xFixed = i32;
+
+#[derive(Clone)]
+struct xPointFixed {
+    x: xFixed,
+    y: xFixed,
+}
+
+#[derive(Clone)]
+struct xLineFixed {
+    p1: xPointFixed,
+    p2: xPointFixed,
+}
+
+#[derive(Clone)]
+struct xTrapezoid {
+    top: xFixed,
+    bottom: xFixed,
+    left: xLineFixed,
+    right: xLineFixed,
+}
+
+fn ToXFixed(r: f32) -> xFixed
+{
+    return (r * 256.0).round() as xFixed;
+}
+
+struct CXRenderTrapezoidSink {
+    m_rgTrapezoids: Vec<xTrapezoid>,
+}
+
+impl CXRenderTrapezoidSink {
+    fn new() -> Self
+    {
+        return CXRenderTrapezoidSink { m_rgTrapezoids: Vec::new() };
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   CXRenderTrapezoidSink::AddTrapezoid
+    //
+    //  Synopsis:
+    //      Convert one of this crate's slope-expanded falloff trapezoids
+    //      into an xTrapezoid by subtracting back out the expand deltas
+    //      to recover the true (non-overlapping) left/right edges, then
+    //      packing each edge's top/bottom endpoints as xLineFixed.
+    //
+    //-------------------------------------------------------------------------
+    fn AddTrapezoid(&mut self,
+        rPixelYTop: f32, rPixelXTopLeft: f32, rPixelXTopRight: f32,
+        rPixelYBottom: f32, rPixelXBottomLeft: f32, rPixelXBottomRight: f32,
+        rPixelXLeftDelta: f32, rPixelXRightDelta: f32
+        ) -> HRESULT
+    {
+        let hr = S_OK;
+
+        let trapezoid = xTrapezoid {
+            top: ToXFixed(rPixelYTop),
+            bottom: ToXFixed(rPixelYBottom),
+            left: xLineFixed {
+                p1: xPointFixed { x: ToXFixed(rPixelXTopLeft + rPixelXLeftDelta),    y: ToXFixed(rPixelYTop) },
+                p2: xPointFixed { x: ToXFixed(rPixelXBottomLeft + rPixelXLeftDelta), y: ToXFixed(rPixelYBottom) },
+            },
+            right: xLineFixed {
+                p1: xPointFixed { x: ToXFixed(rPixelXTopRight - rPixelXRightDelta),    y: ToXFixed(rPixelYTop) },
+                p2: xPointFixed { x: ToXFixed(rPixelXBottomRight - rPixelXRightDelta), y: ToXFixed(rPixelYBottom) },
+            },
+        };
+
+        self.m_rgTrapezoids.push(trapezoid);
+
+        return hr;
+    }
+}
+
+impl IGeometrySink for CXRenderTrapezoidSink {
+    // This sink only exists to collect the trapezoidal fast path's
+    // output as xTrapezoids; there's no xRender representation for an
+    // arbitrary complex-scan coverage run, so report it unsupported
+    // rather than silently dropping coverage.
+    fn AddComplexScan(&mut self, _nPixelY: INT, _pIntervalSpanStart: &CCoverageInterval) -> HRESULT
+    {
+        return E_NOTIMPL;
+    }
+
+    fn AddTrapezoid(&mut self,
+        rPixelYTop: f32, rPixelXTopLeft: f32, rPixelXTopRight: f32,
+        rPixelYBottom: f32, rPixelXBottomLeft: f32, rPixelXBottomRight: f32,
+        rPixelXLeftDelta: f32, rPixelXRightDelta: f32
+        ) -> HRESULT
+    {
+        return CXRenderTrapezoidSink::AddTrapezoid(
+            self, rPixelYTop, rPixelXTopLeft, rPixelXTopRight,
+            rPixelYBottom, rPixelXBottomLeft, rPixelXBottomRight,
+            rPixelXLeftDelta, rPixelXRightDelta
+            );
+    }
+}
+
+impl CHwRasterizer {
+
+//-------------------------------------------------------------------------
+//
+//  Function:   CHwRasterizer::RasterizeToXRenderTrapezoids
+//
+//  Synopsis:
+//      Alternate entry point selectable alongside SendGeometry/
+//      RasterizeToA8Buffer: rasterize the path through the trapezoidal
+//      path as usual, but collect the result as CXRenderTrapezoidSink's
+//      xTrapezoid list instead of feeding an IGeometrySink/GPU pipeline
+//      or an A8 buffer.
+//
+//-------------------------------------------------------------------------
+fn RasterizeToXRenderTrapezoids(&mut self) -> Result<Vec<xTrapezoid>, HRESULT>
+{
+    let sink = Rc::new(CXRenderTrapezoidSink::new());
+
+    // Keep a second handle before SendGeometry takes ownership of the
+    // first one -- sink.m_rgTrapezoids can't be read after sink itself
+    // has been moved into Rc::new()/SendGeometry, so the result has to
+    // come back out through this clone instead.
+    let sinkForResult = Rc::clone(&sink);
+
+    let hr = self.SendGeometry(sink);
+    if (FAILED(hr) && hr != WGXHR_EMPTYFILL)
+    {
+        return Err(hr);
+    }
+
+    let rgTrapezoids = match Rc::try_unwrap(sinkForResult) {
+        Ok(sink) => sink.m_rgTrapezoids,
+        Err(sharedSink) => sharedSink.m_rgTrapezoids.clone(),
+    };
+
+    return Ok(rgTrapezoids);
+}
+
+} // impl CHwRasterizer
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   shift_is_log2_of_sample_count
+    //
+    //  Synopsis:
+    //      Pins SamplePattern::Shift() to log2(SampleCount()) for every
+    //      pattern, i.e. 1 << Shift() == SampleCount(). Regression test for
+    //      a bug where X4/X8/X16 returned their sample counts (or other
+    //      unrelated constants) instead of the actual shift.
+    //
+    //-------------------------------------------------------------------------
+    #[test]
+    fn shift_is_log2_of_sample_count()
+    {
+        let rgPatterns = [
+            SamplePattern::X1,
+            SamplePattern::X2,
+            SamplePattern::X4,
+            SamplePattern::X8,
+            SamplePattern::X16,
+            ];
+
+        for pattern in &rgPatterns
+        {
+            assert_eq!(1u32 << pattern.Shift(), pattern.SampleCount());
+        }
+    }
+
+    fn make_edge(X: INT, Dx: INT, Error: INT, ErrorUp: INT, ErrorDown: INT) -> Box<CEdge>
+    {
+        Box::new(CEdge {
+            X,
+            StartY: 0,
+            EndY: i32::MAX,
+            WindingDirection: 1,
+            Dx,
+            ErrorUp,
+            ErrorDown,
+            Error,
+            Next: NonNull::dangling(),
+        })
+    }
+
+    //-------------------------------------------------------------------------
+    //
+    //  Function:   error_advance_needs_64_bits
+    //
+    //  Synopsis:
+    //      Pins AdvanceDDAOneEdgeScalar and AdvanceDDAMultipleStepsSimd8 to
+    //      bit-identical output -- including on an nSubpixelYAdvance*ErrorUp
+    //      product (1<<20 * 1<<16 == 1<<36) that genuinely overflows a
+    //      32-bit multiply, so a regression that drops the 64-bit widening
+    //      from either kernel would change its (X, Error) result rather
+    //      than silently matching.  Falls back to comparing the scalar
+    //      kernel against itself when the target/CPU doesn't have the AVX2
+    //      kernel this crate can build for, so the test still exercises the
+    //      overflow-safe arithmetic everywhere.
+    //
+    //-------------------------------------------------------------------------
+    #[test]
+    fn error_advance_needs_64_bits()
+    {
+        let nSubpixelYAdvance: INT = 1 << 20;
+        let nErrorUp: INT = 1 << 16;
+
+        assert_eq!(nSubpixelYAdvance.wrapping_mul(nErrorUp), 0,
+            "sanity check: these inputs should actually overflow i32");
+
+        let mut edge = make_edge(/* X */ 0, /* Dx */ 3, /* Error */ -5, nErrorUp, /* ErrorDown */ 1 << 20);
+
+        let mut nXScalar = 0;
+        let mut nErrorScalar = 0;
+        unsafe {
+            AdvanceDDAOneEdgeScalar(NonNull::from(edge.as_mut()), nSubpixelYAdvance, &mut nXScalar, &mut nErrorScalar);
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            if (is_x86_feature_detected!("avx2"))
+            {
+                let rgEdges: [NonNull<CEdge>; c_nSimdLaneCount] = [NonNull::from(edge.as_mut()); c_nSimdLaneCount];
+                let mut rgX = [0; c_nSimdLaneCount];
+                let mut rgError = [0; c_nSimdLaneCount];
+
+                unsafe {
+                    AdvanceDDAMultipleStepsSimd8(&rgEdges, nSubpixelYAdvance, &mut rgX, &mut rgError);
+                }
+
+                for i in 0..c_nSimdLaneCount
+                {
+                    assert_eq!(rgX[i], nXScalar, "SIMD and scalar X must agree exactly (lane {})", i);
+                    assert_eq!(rgError[i], nErrorScalar, "SIMD and scalar Error must agree exactly (lane {})", i);
+                }
+
+                return;
+            }
+        }
+
+        // No AVX2 kernel on this target/CPU: re-run the scalar kernel to
+        // confirm it's at least deterministic on the overflow-prone input.
+        let mut nXScalar2 = 0;
+        let mut nErrorScalar2 = 0;
+        unsafe {
+            AdvanceDDAOneEdgeScalar(NonNull::from(edge.as_mut()), nSubpixelYAdvance, &mut nXScalar2, &mut nErrorScalar2);
+        }
+        assert_eq!(nXScalar2, nXScalar);
+        assert_eq!(nErrorScalar2, nErrorScalar);
+    }
+}